@@ -0,0 +1,21 @@
+fn main() {
+    // Compile the C++ side of the bridge in src/cpp.rs
+    #[cfg(feature = "cxx")]
+    {
+        cxx_build::bridge("src/cpp.rs")
+            .std("c++14")
+            .compile("atomicslice-cxx");
+        println!("cargo:rerun-if-changed=src/cpp.rs");
+    }
+
+    // The loom feature is the same as compiling with --cfg loom, which the
+    // crate checks for everywhere
+    #[cfg(feature = "loom")]
+    println!("cargo:rustc-cfg=loom");
+
+    // Likewise, the shuttle feature sets --cfg shuttle
+    #[cfg(feature = "shuttle")]
+    println!("cargo:rustc-cfg=shuttle");
+
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,49 @@
+use alloc::vec::Vec;
+
+use crate::{storage::Storage, AtomicSlice};
+
+impl<T: Clone, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Create a new `AtomicSlice` like `new_with_buffers`, whose partitions
+    /// each start at an address that is a multiple of `align` bytes, which
+    /// must be a power of two, otherwise this method panics. The slices that
+    /// read lock guards and writers see are thus aligned accordingly, which
+    /// SIMD code can use for aligned loads and stores.
+    ///
+    /// ```
+    /// use atomicslice::AtomicSlice;
+    ///
+    /// let atomic_slice: AtomicSlice<f32> = AtomicSlice::new_aligned(vec![0.0; 10], 64);
+    /// assert_eq!(atomic_slice.read().as_ptr().addr() % 64, 0);
+    /// ```
+    ///
+    /// To keep every partition aligned, each one is followed by as many
+    /// padding elements as needed, which are clones of the data's elements
+    /// in order, starting over from the first one whenever they run out.
+    pub fn new_aligned(data: Vec<T>, align: usize) -> AtomicSlice<T, BUFS> {
+        if !align.is_power_of_two() {
+            panic!("Attempted to create AtomicSlice with an alignment that isn't a power of two");
+        }
+
+        let stride = data.len();
+        let pitch = aligned_pitch::<T>(stride, align);
+        let total = pitch
+            .checked_mul(BUFS)
+            .expect("Attempted to create AtomicSlice whose partitions don't fit into memory");
+        let storage = Storage::new_aligned(total, align, |i| data[(i % pitch) % stride].clone());
+        AtomicSlice::from_partitions(storage, stride, pitch)
+    }
+}
+
+/// The smallest number of elements, no fewer than `stride`, that takes up a
+/// multiple of `align` bytes
+fn aligned_pitch<T>(stride: usize, align: usize) -> usize {
+    let size = core::mem::size_of::<T>();
+    if size == 0 {
+        return stride;
+    }
+    // The number of elements that takes up the least common multiple of
+    // the two sizes, both of whose greatest common divisor is a power of two
+    let gcd = 1 << size.trailing_zeros().min(align.trailing_zeros());
+    let unit = align / gcd;
+    stride.div_ceil(unit) * unit
+}
@@ -0,0 +1,59 @@
+use alloc::vec::Vec;
+
+use allocator_api2::alloc::Allocator;
+
+#[cfg(target_has_atomic = "ptr")]
+use crate::snapshot::SnapshotCache;
+use crate::{protocol::Protocol, storage::Storage, AtomicSlice};
+
+impl<T: Clone, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Create a new `AtomicSlice` like `new_with_buffers`, but allocate the
+    /// partitions using the given allocator instead of the global one. The
+    /// elements are moved out of the vector, whose own allocation is then
+    /// given back to the global allocator.
+    ///
+    /// The allocator is kept until the `AtomicSlice` is dropped. It must be
+    /// `'static`, since the `AtomicSlice` has no lifetime to tie it to, which
+    /// a reference to an allocator that lives for the rest of the program
+    /// satisfies. Allocators that aren't zero-sized are boxed using the global
+    /// allocator.
+    ///
+    /// With the `nightly` feature of `allocator-api2`, its `Allocator` trait
+    /// is the standard library's, so that any allocator implementing
+    /// `std::alloc::Allocator` can be used.
+    pub fn new_in<A: Allocator + Send + Sync + 'static>(
+        data: Vec<T>,
+        allocator: A,
+    ) -> AtomicSlice<T, BUFS> {
+        let mut data_in = allocator_api2::vec::Vec::with_capacity_in(data.len() * BUFS, allocator);
+        data_in.extend(data);
+        AtomicSlice::from_vec_in(data_in)
+    }
+
+    /// Create a new `AtomicSlice` from a vector that was allocated using a
+    /// custom allocator, which is then used for all partitions. The vector's
+    /// allocation is grown to hold the other partitions, and reused if it
+    /// is large enough already. See `new_in`.
+    pub fn from_vec_in<A: Allocator + Send + Sync + 'static>(
+        mut data: allocator_api2::vec::Vec<T, A>,
+    ) -> AtomicSlice<T, BUFS> {
+        let protocol = Protocol::new();
+
+        let stride = data.len();
+        // Fill the other partitions with copies of the initial data
+        data.reserve_exact(stride * (BUFS - 1));
+        for _ in 1..BUFS {
+            data.extend_from_within(..stride);
+        }
+        AtomicSlice {
+            data: Storage::new_in(data),
+            stride,
+            pitch: stride,
+            protocol,
+            #[cfg(all(feature = "mlock", unix))]
+            locked: false,
+            #[cfg(target_has_atomic = "ptr")]
+            snapshot: SnapshotCache::new(),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use alloc::{sync::Arc, vec::Vec};
+use core::{
+    cell::UnsafeCell,
+    panic::{RefUnwindSafe, UnwindSafe},
+};
+
+use crate::{
+    protocol::{release, Protocol},
+    AtomicSliceReadGuard, Backoff,
+};
+
+/// A variant of `AtomicSlice` which publishes reference-counted slices
+/// instead of copying new data into a partition, in the style of
+/// read-copy-update. Each of the `BUFS` partitions merely holds an
+/// `Arc<[T]>`, so writing swaps in a slice that was already built elsewhere
+/// without copying any elements, which suits very large slices that are
+/// replaced as a whole. Readers can either lock the current slice like with
+/// `AtomicSlice`, or take a reference-counted pointer to it which they may
+/// keep for as long as they like without holding up any writers.
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use atomicslice::AtomicArcSlice;
+///
+/// let table = AtomicArcSlice::new(Arc::from(vec![0.0; 1 << 20]));
+/// table.write(Arc::from(vec![1.0; 1 << 20]));
+/// let snapshot: Arc<[f32]> = table.load();
+/// assert_eq!(snapshot[0], 1.0);
+/// ```
+///
+/// Like with `AtomicVec`, each write may have a different length.
+pub struct AtomicArcSlice<T, const BUFS: usize = 2> {
+    data: UnsafeCell<[Arc<[T]>; BUFS]>,
+    protocol: Protocol<BUFS>,
+}
+
+impl<T> AtomicArcSlice<T> {
+    /// Create a new `AtomicArcSlice` holding the given slice
+    pub fn new(data: Arc<[T]>) -> AtomicArcSlice<T> {
+        AtomicArcSlice::new_with_buffers(data)
+    }
+}
+
+impl<T, const BUFS: usize> AtomicArcSlice<T, BUFS> {
+    /// Create a new `AtomicArcSlice` with `BUFS` partitions. Otherwise
+    /// identical to `new`.
+    pub fn new_with_buffers(data: Arc<[T]>) -> AtomicArcSlice<T, BUFS> {
+        let protocol = Protocol::new();
+        AtomicArcSlice {
+            data: UnsafeCell::new(core::array::from_fn(|_| Arc::clone(&data))),
+            protocol,
+        }
+    }
+
+    /// Get the version of the most recently published data. See
+    /// `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.protocol.version()
+    }
+
+    /// Acquire a read lock on the most recently published slice. See
+    /// `AtomicSlice::read`.
+    pub fn read(&self) -> AtomicSliceReadGuard<'_, T, BUFS> {
+        let current_slice = self.protocol.acquire();
+        let slice: &[T] = unsafe { self.arc(current_slice) };
+        let version = unsafe { self.protocol.slice_version(current_slice) };
+
+        AtomicSliceReadGuard {
+            slice,
+            current_slice,
+            status: self.protocol.status(),
+            version,
+        }
+    }
+
+    /// Get a reference-counted pointer to the most recently published slice.
+    /// The read lock is only held while the reference count is incremented,
+    /// so the returned pointer never holds up any writers.
+    pub fn load(&self) -> Arc<[T]> {
+        let current_slice = self.protocol.acquire();
+        let arc = Arc::clone(unsafe { self.arc(current_slice) });
+        release::<BUFS>(self.protocol.status(), current_slice);
+        arc
+    }
+
+    /// Block the current thread until a version newer than `since_version`
+    /// has been published. See `AtomicSlice::wait_for_update`.
+    #[cfg(feature = "std")]
+    pub fn wait_for_update(&self, since_version: u64) -> u64 {
+        self.protocol.wait_for_update(since_version)
+    }
+
+    /// Choose how writers wait. See `AtomicSlice::set_backoff`.
+    pub fn set_backoff(&mut self, backoff: Backoff) {
+        self.protocol.set_backoff(backoff);
+    }
+
+    /// Returns true if a thread panicked while writing. See
+    /// `AtomicSlice::is_poisoned`.
+    #[cfg(feature = "std")]
+    pub fn is_poisoned(&self) -> bool {
+        self.protocol.is_poisoned()
+    }
+
+    /// Clear the poisoned state after a panic while writing. See
+    /// `AtomicSlice::clear_poison`.
+    #[cfg(feature = "std")]
+    pub fn clear_poison(&self) {
+        self.protocol.clear_poison();
+    }
+
+    /// Publish the given slice without copying any of its elements. The slice
+    /// that it replaces in the partition being written to is dropped, unless
+    /// anyone else still holds a pointer to it.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn write(&self, data: Arc<[T]>) {
+        let _lock = self.protocol.lock_writer();
+
+        // Wait to ensure the next partition is not being used
+        let next_i = self.protocol.wait_for_free_slice();
+        let previous = core::mem::replace(unsafe { self.arc_mut(next_i) }, data);
+
+        self.protocol.publish(next_i);
+
+        // Drop the previous slice only after publishing, since this may
+        // involve dropping all of its elements
+        drop(previous);
+    }
+
+    /// Publish a vector of new data. See `write`.
+    pub fn write_vec(&self, data: Vec<T>) {
+        self.write(Arc::from(data));
+    }
+
+    /// Get shared access to the pointer in one of the partitions.
+    ///
+    /// Safety: the partition must not be written to while the returned
+    /// reference is alive, i.e. the caller must either hold a read lock on it
+    /// or hold exclusive access to the write portion.
+    unsafe fn arc(&self, slice: u8) -> &Arc<[T]> {
+        &*(self.data.get() as *const Arc<[T]>).add(slice as usize)
+    }
+
+    /// Get mutable access to the pointer in one of the partitions.
+    ///
+    /// Safety: the caller must hold exclusive access to the write portion
+    /// and the partition must not be in use by any readers.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn arc_mut(&self, slice: u8) -> &mut Arc<[T]> {
+        &mut *(self.data.get() as *mut Arc<[T]>).add(slice as usize)
+    }
+}
+
+unsafe impl<T: Send + Sync, const BUFS: usize> Sync for AtomicArcSlice<T, BUFS> {}
+unsafe impl<T: Send + Sync, const BUFS: usize> Send for AtomicArcSlice<T, BUFS> {}
+impl<T: RefUnwindSafe, const BUFS: usize> RefUnwindSafe for AtomicArcSlice<T, BUFS> {}
+impl<T: UnwindSafe, const BUFS: usize> UnwindSafe for AtomicArcSlice<T, BUFS> {}
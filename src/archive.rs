@@ -0,0 +1,95 @@
+use alloc::vec::Vec;
+
+use rkyv::{
+    api::high::{HighDeserializer, HighSerializer, HighValidator},
+    bytecheck::CheckBytes,
+    rancor::{Error, Fallible, Source},
+    ser::{allocator::ArenaHandle, Allocator, Writer},
+    util::AlignedVec,
+    vec::{ArchivedVec, VecResolver},
+    Archive, Deserialize, Place, Serialize,
+};
+
+use crate::{AtomicSlice, LengthMismatch};
+
+/// Archives a borrowed slice exactly like a `Vec` of the same elements,
+/// without having to copy them into one first
+struct AsVec<'a, T>(&'a [T]);
+
+impl<T: Archive> Archive for AsVec<'_, T> {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: VecResolver, out: Place<Self::Archived>) {
+        ArchivedVec::resolve_from_slice(self.0, resolver, out);
+    }
+}
+
+impl<T: Serialize<S>, S: Fallible + Allocator + Writer + ?Sized> Serialize<S> for AsVec<'_, T> {
+    fn serialize(&self, serializer: &mut S) -> Result<VecResolver, S::Error> {
+        ArchivedVec::serialize_from_slice(self.0, serializer)
+    }
+}
+
+impl<T: Archive, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Archive a snapshot of the current contents using rkyv, taken under a
+    /// single read lock. The archive has the same layout as that of a `Vec`
+    /// holding the same elements, so that its contents can be accessed
+    /// without deserializing them using `rkyv::access` with an
+    /// `ArchivedVec<T::Archived>`, such as after mapping it into memory or
+    /// receiving it from another process.
+    ///
+    /// ```
+    /// use atomicslice::AtomicSlice;
+    /// use rkyv::{rancor::Error, vec::ArchivedVec};
+    ///
+    /// let samples = AtomicSlice::new(vec![0.25_f32, 0.5, 1.0]);
+    /// let bytes = samples.to_archive().unwrap();
+    ///
+    /// let archived = rkyv::access::<ArchivedVec<rkyv::Archived<f32>>, Error>(&bytes).unwrap();
+    /// assert_eq!(archived[2], 1.0);
+    ///
+    /// let copy = AtomicSlice::<f32>::from_archive(&bytes).unwrap();
+    /// assert_eq!(*copy.read(), [0.25, 0.5, 1.0]);
+    /// ```
+    pub fn to_archive(&self) -> Result<AlignedVec, Error>
+    where
+        T: for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, Error>>,
+    {
+        rkyv::to_bytes(&AsVec(&self.read()))
+    }
+
+    /// Validate an archive made by `to_archive` and write its contents, which
+    /// must have the same length as the `AtomicSlice` itself, otherwise this
+    /// returns an error and writes nothing. Like `write_vec`, this may block.
+    pub fn write_from_archive(&self, bytes: &[u8]) -> Result<(), Error>
+    where
+        T::Archived:
+            for<'a> CheckBytes<HighValidator<'a, Error>> + Deserialize<T, HighDeserializer<Error>>,
+    {
+        let archived = rkyv::access::<ArchivedVec<T::Archived>, Error>(bytes)?;
+        if archived.len() != self.len() {
+            return Err(Error::new(LengthMismatch {
+                expected: self.len(),
+                actual: archived.len(),
+            }));
+        }
+        let data: Vec<T> = rkyv::deserialize(archived)?;
+        self.write_vec(data);
+        Ok(())
+    }
+}
+
+impl<T: Archive + Clone, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Validate an archive made by `to_archive` and create a new
+    /// `AtomicSlice` holding its contents, which decide its length.
+    pub fn from_archive(bytes: &[u8]) -> Result<AtomicSlice<T, BUFS>, Error>
+    where
+        T::Archived:
+            for<'a> CheckBytes<HighValidator<'a, Error>> + Deserialize<T, HighDeserializer<Error>>,
+    {
+        let archived = rkyv::access::<ArchivedVec<T::Archived>, Error>(bytes)?;
+        let data: Vec<T> = rkyv::deserialize(archived)?;
+        Ok(AtomicSlice::new_with_buffers(data))
+    }
+}
@@ -0,0 +1,164 @@
+use core::{
+    cell::UnsafeCell,
+    panic::{RefUnwindSafe, UnwindSafe},
+};
+
+use crate::{protocol::Protocol, AtomicSliceReadGuard, Backoff, TryWriteError};
+
+/// A fixed-size array of `N` elements that can be written and read from
+/// multiple threads, just like an `AtomicSlice`. Unlike an `AtomicSlice`,
+/// both copies of the data are stored inline without any heap allocation,
+/// and an `AtomicArray` can be created in a constant context, making it
+/// suitable for use in a `static`:
+///
+/// ```
+/// use atomicslice::AtomicArray;
+///
+/// static PARAMS: AtomicArray<f32, 4> = AtomicArray::new([0.0; 4]);
+///
+/// PARAMS.write(&[1.0, 2.0, 3.0, 4.0]);
+/// assert_eq!(*PARAMS.read(), [1.0, 2.0, 3.0, 4.0]);
+/// ```
+///
+/// Reading returns the same lock guard as `AtomicSlice::read`.
+pub struct AtomicArray<T, const N: usize> {
+    data: UnsafeCell<[[T; N]; 2]>,
+    protocol: Protocol<2>,
+}
+
+impl<T: Copy, const N: usize> AtomicArray<T, N> {
+    /// Create a new `AtomicArray` holding the given data. Since this is a
+    /// `const fn`, it can be used to initialize a `static`.
+    pub const fn new(data: [T; N]) -> AtomicArray<T, N> {
+        AtomicArray {
+            data: UnsafeCell::new([data, data]),
+            protocol: Protocol::new(),
+        }
+    }
+
+    /// Choose how writers wait, like `set_backoff`, but in a constant
+    /// context such as the initializer of a `static`.
+    pub const fn with_backoff(mut self, backoff: Backoff) -> AtomicArray<T, N> {
+        self.protocol.set_backoff(backoff);
+        self
+    }
+}
+
+impl<T, const N: usize> AtomicArray<T, N> {
+    /// Get the number of elements
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns true if the array has no elements
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Get the version of the most recently published data. See
+    /// `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.protocol.version()
+    }
+
+    /// Acquire a read lock on the array. See `AtomicSlice::read`.
+    pub fn read(&self) -> AtomicSliceReadGuard<'_, T> {
+        let current_slice = self.protocol.acquire();
+        let slice: &[T] = unsafe { self.array(current_slice) };
+        let version = unsafe { self.protocol.slice_version(current_slice) };
+
+        AtomicSliceReadGuard {
+            slice,
+            current_slice,
+            status: self.protocol.status(),
+            version,
+        }
+    }
+
+    /// Block the current thread until a version newer than `since_version`
+    /// has been published. See `AtomicSlice::wait_for_update`.
+    #[cfg(feature = "std")]
+    pub fn wait_for_update(&self, since_version: u64) -> u64 {
+        self.protocol.wait_for_update(since_version)
+    }
+
+    /// Choose how writers wait. See `AtomicSlice::set_backoff`.
+    pub fn set_backoff(&mut self, backoff: Backoff) {
+        self.protocol.set_backoff(backoff);
+    }
+
+    /// Returns true if a thread panicked while writing. See
+    /// `AtomicSlice::is_poisoned`.
+    #[cfg(feature = "std")]
+    pub fn is_poisoned(&self) -> bool {
+        self.protocol.is_poisoned()
+    }
+
+    /// Clear the poisoned state after a panic while writing. See
+    /// `AtomicSlice::clear_poison`.
+    #[cfg(feature = "std")]
+    pub fn clear_poison(&self) {
+        self.protocol.clear_poison();
+    }
+
+    /// Write new data in place. See `AtomicSlice::write_with`.
+    pub fn write_with<F: FnOnce(&mut [T; N])>(&self, f: F) {
+        let _lock = self.protocol.lock_writer();
+
+        // Wait to ensure the next array is not being used
+        let next_i = self.protocol.wait_for_free_slice();
+
+        // Let the caller fill in the next array
+        f(unsafe { self.array_mut(next_i) });
+
+        self.protocol.publish(next_i);
+    }
+
+    /// Get shared access to one of the two arrays.
+    ///
+    /// Safety: the array must not be written to while the returned reference
+    /// is alive, i.e. the caller must either hold a read lock on it or hold
+    /// exclusive access to the write portion.
+    unsafe fn array(&self, slice: u8) -> &[T; N] {
+        &*(self.data.get() as *const [T; N]).add(slice as usize)
+    }
+
+    /// Get mutable access to one of the two arrays.
+    ///
+    /// Safety: the caller must hold exclusive access to the write portion
+    /// and the array must not be in use by any readers.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn array_mut(&self, slice: u8) -> &mut [T; N] {
+        &mut *(self.data.get() as *mut [T; N]).add(slice as usize)
+    }
+}
+
+impl<T: Clone, const N: usize> AtomicArray<T, N> {
+    /// Write new data. This method may block if other threads are writing
+    /// and if any readers are holding lock guards for extended periods of time.
+    pub fn write(&self, data: &[T; N]) {
+        self.write_with(|array| array.clone_from_slice(data));
+    }
+
+    /// Attempt to write new data without waiting. See
+    /// `AtomicSlice::try_write`.
+    pub fn try_write(&self, data: &[T; N]) -> Result<(), TryWriteError> {
+        let Some(_lock) = self.protocol.try_lock_writer() else {
+            return Err(TryWriteError::WriteInProgress);
+        };
+
+        let Some(next_i) = self.protocol.free_slice() else {
+            return Err(TryWriteError::ReadInProgress);
+        };
+
+        unsafe { self.array_mut(next_i) }.clone_from_slice(data);
+
+        self.protocol.publish(next_i);
+        Ok(())
+    }
+}
+
+unsafe impl<T: Send + Sync, const N: usize> Sync for AtomicArray<T, N> {}
+unsafe impl<T: Send, const N: usize> Send for AtomicArray<T, N> {}
+impl<T: RefUnwindSafe, const N: usize> RefUnwindSafe for AtomicArray<T, N> {}
+impl<T: UnwindSafe, const N: usize> UnwindSafe for AtomicArray<T, N> {}
@@ -0,0 +1,90 @@
+use crate::sync::{spin_loop, wait_forever, SINGLE_THREADED};
+
+/// How a writer waits for competing writers to finish and for readers to
+/// release the partition it wants to write to. This only affects writers,
+/// since reading never waits.
+///
+/// Writers are let in first come, first served, so that a writer whose turn
+/// it is may have to be scheduled before any other writer can carry on. On
+/// machines with more busy threads than cores, writers that only ever spin
+/// can thus end up waiting for a whole time slice every time a competing
+/// writer is done. With `std`, writers therefore default to `SpinThenYield`,
+/// and to `Spin` otherwise.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backoff {
+    /// Check again after a single spin loop hint each time. This reacts the
+    /// fastest, but keeps a whole core busy for as long as the writer waits.
+    #[cfg_attr(not(feature = "std"), default)]
+    Spin,
+    /// Double the number of spin loop hints between checks each time, up to
+    /// a limit. This eases contention on the status word and the writer lock
+    /// while reacting almost as quickly.
+    Exponential,
+    /// Back off exponentially at first, then yield to the operating system's
+    /// scheduler between checks. This is the kindest choice on machines with
+    /// more busy threads than cores, where the reader being waited for may
+    /// not even be running.
+    #[cfg(feature = "std")]
+    #[default]
+    SpinThenYield,
+    /// Park the writer's thread until it is woken up by whichever reader
+    /// releases the partition being waited for, or by the competing writer
+    /// that finishes writing. Waiting costs nothing at all, but waking up
+    /// takes longer, and releasing a read lock becomes more expensive while
+    /// a writer is parked. Since `write_timeout` has to keep an eye on the
+    /// time, it backs off like `SpinThenYield` instead. Parking traps on the
+    /// main thread of a browser, which isn't allowed to block.
+    #[cfg(feature = "std")]
+    Park,
+}
+
+impl Backoff {
+    /// The same as `Backoff::default()`, for use in constant contexts
+    #[cfg(feature = "std")]
+    pub(crate) const DEFAULT: Backoff = Backoff::SpinThenYield;
+
+    /// The same as `Backoff::default()`, for use in constant contexts
+    #[cfg(not(feature = "std"))]
+    pub(crate) const DEFAULT: Backoff = Backoff::Spin;
+}
+
+/// The number of times that the number of spin loop hints is doubled
+const MAX_STEP: u32 = 6;
+
+/// The state of a single writer waiting according to a `Backoff` policy
+pub(crate) struct Waiter {
+    backoff: Backoff,
+    step: u32,
+}
+
+impl Waiter {
+    pub(crate) fn new(backoff: Backoff) -> Waiter {
+        Waiter { backoff, step: 0 }
+    }
+
+    /// Wait a little before checking again
+    pub(crate) fn wait(&mut self) {
+        if SINGLE_THREADED {
+            wait_forever();
+        }
+        match self.backoff {
+            Backoff::Spin => spin_loop(),
+            Backoff::Exponential => self.spin_exponentially(),
+            #[cfg(feature = "std")]
+            Backoff::SpinThenYield | Backoff::Park => {
+                if self.step < MAX_STEP {
+                    self.spin_exponentially();
+                } else {
+                    crate::sync::yield_now();
+                }
+            }
+        }
+    }
+
+    fn spin_exponentially(&mut self) {
+        for _ in 0..1 << self.step {
+            spin_loop();
+        }
+        self.step = (self.step + 1).min(MAX_STEP);
+    }
+}
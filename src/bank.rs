@@ -0,0 +1,176 @@
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+use crate::{AtomicSlice, AtomicSliceReadGuard, MappedAtomicSliceReadGuard};
+
+/// A bank of channels of equal length, such as those of a multichannel
+/// audio bus, which are stored one after the other in a single allocation
+/// and share a single status word. Individual channels can be written on
+/// their own, while writing several channels at once publishes them all
+/// together, so that readers never see some of them updated but not others.
+///
+/// ```
+/// use atomicslice::AtomicSliceBank;
+///
+/// let bus = AtomicSliceBank::new(2, vec![0.0; 4]);
+/// bus.write_channels(&[&[1.0; 4], &[2.0; 4]]);
+/// assert_eq!(*bus.read_channel(1), [2.0; 4]);
+/// ```
+pub struct AtomicSliceBank<T, const BUFS: usize = 2> {
+    inner: AtomicSlice<T, BUFS>,
+    channels: usize,
+    channel_len: usize,
+}
+
+/// A read lock guard on all channels of an `AtomicSliceBank`, which
+/// dereferences to all of them one after the other.
+pub struct AtomicSliceBankReadGuard<'a, T, const BUFS: usize = 2> {
+    guard: AtomicSliceReadGuard<'a, T, BUFS>,
+    channels: usize,
+    channel_len: usize,
+}
+
+impl<T: Clone> AtomicSliceBank<T> {
+    /// Create a new `AtomicSliceBank` with the given number of channels,
+    /// each of which starts out with a copy of the given data
+    pub fn new(channels: usize, data: Vec<T>) -> AtomicSliceBank<T> {
+        AtomicSliceBank::new_with_buffers(channels, data)
+    }
+}
+
+impl<T: Clone, const BUFS: usize> AtomicSliceBank<T, BUFS> {
+    /// Create a new `AtomicSliceBank` with `BUFS` partitions. Otherwise
+    /// identical to `new`.
+    pub fn new_with_buffers(channels: usize, data: Vec<T>) -> AtomicSliceBank<T, BUFS> {
+        let channel_len = data.len();
+        let mut all = Vec::with_capacity(channels * channel_len);
+        for _ in 0..channels {
+            all.extend_from_slice(&data);
+        }
+        AtomicSliceBank {
+            inner: AtomicSlice::new_with_buffers(all),
+            channels,
+            channel_len,
+        }
+    }
+
+    /// Write new data to a single channel, while all other channels keep
+    /// their currently published contents. The channel must exist and the
+    /// data must have the length of a channel, otherwise this method panics.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn write_channel(&self, channel: usize, data: &[T]) {
+        if channel >= self.channels || data.len() != self.channel_len {
+            panic!("Attempted to write channel of the wrong length or out of bounds to AtomicSliceBank");
+        }
+        self.inner.write_range(channel * self.channel_len, data);
+    }
+
+    /// Write new data to every channel and publish them all together. There
+    /// must be one slice per channel, each of which must have the length of a
+    /// channel, otherwise this method panics.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn write_channels(&self, data: &[&[T]]) {
+        if data.len() != self.channels || data.iter().any(|d| d.len() != self.channel_len) {
+            panic!("Attempted to write channels of the wrong length to AtomicSliceBank");
+        }
+        self.write_with(|channel, slice| slice.clone_from_slice(data[channel]));
+    }
+}
+
+impl<T, const BUFS: usize> AtomicSliceBank<T, BUFS> {
+    /// Get the number of channels
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Get the number of elements of each channel
+    pub fn channel_len(&self) -> usize {
+        self.channel_len
+    }
+
+    /// Get the version of the most recently published data. See
+    /// `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
+    /// Acquire a read lock on all channels at once, which are guaranteed to
+    /// have been published together. See `AtomicSlice::read`.
+    pub fn read(&self) -> AtomicSliceBankReadGuard<'_, T, BUFS> {
+        AtomicSliceBankReadGuard {
+            guard: self.inner.read(),
+            channels: self.channels,
+            channel_len: self.channel_len,
+        }
+    }
+
+    /// Acquire a read lock on a single channel, or panic if it doesn't exist
+    pub fn read_channel(&self, channel: usize) -> MappedAtomicSliceReadGuard<'_, T, BUFS> {
+        if channel >= self.channels {
+            panic!("Attempted to read channel out of bounds of AtomicSliceBank");
+        }
+        let range = channel * self.channel_len..(channel + 1) * self.channel_len;
+        self.inner.read().map(|slice| &slice[range])
+    }
+
+    /// Write new data to every channel in place and publish them all
+    /// together. The given function is called once for each channel with its
+    /// index and mutable access to its elements in the inactive partition,
+    /// which still hold stale data like with `AtomicSlice::write_with`.
+    pub fn write_with<F: FnMut(usize, &mut [T])>(&self, mut f: F) {
+        let channel_len = self.channel_len;
+        let channels = self.channels;
+        self.inner.write_with(|slice| {
+            for channel in 0..channels {
+                f(
+                    channel,
+                    &mut slice[channel * channel_len..(channel + 1) * channel_len],
+                );
+            }
+        });
+    }
+
+    /// Get the underlying `AtomicSlice`, which holds all channels one after
+    /// the other
+    pub fn as_flat(&self) -> &AtomicSlice<T, BUFS> {
+        &self.inner
+    }
+}
+
+impl<'a, T, const BUFS: usize> Deref for AtomicSliceBankReadGuard<'a, T, BUFS> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.guard
+    }
+}
+
+impl<'a, T, const BUFS: usize> AtomicSliceBankReadGuard<'a, T, BUFS> {
+    /// Get a single channel, or panic if it doesn't exist
+    pub fn channel(&self, channel: usize) -> &[T] {
+        if channel >= self.channels {
+            panic!("Attempted to read channel out of bounds of AtomicSliceBank");
+        }
+        &self.guard[channel * self.channel_len..(channel + 1) * self.channel_len]
+    }
+
+    /// Iterate over all channels
+    pub fn iter_channels(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.channels).map(|channel| self.channel(channel))
+    }
+
+    /// Get the number of channels
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// The version of the data that this guard is holding onto.
+    /// See `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.guard.version()
+    }
+}
@@ -0,0 +1,22 @@
+use bytes::Bytes;
+
+use crate::AtomicSlice;
+
+impl<const BUFS: usize> AtomicSlice<u8, BUFS> {
+    /// Copy the current contents into a new reference-counted `Bytes`, which
+    /// can be handed to network stacks and cloned cheaply from then on. Like
+    /// `to_vec`, the read lock is only held for the duration of the copy,
+    /// which is the only one that is made.
+    ///
+    /// ```
+    /// use atomicslice::AtomicSlice;
+    ///
+    /// let payload = AtomicSlice::new(b"hello".to_vec());
+    /// let snapshot = payload.snapshot_bytes();
+    /// payload.write(b"world");
+    /// assert_eq!(snapshot, "hello");
+    /// ```
+    pub fn snapshot_bytes(&self) -> Bytes {
+        Bytes::copy_from_slice(&self.read())
+    }
+}
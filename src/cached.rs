@@ -0,0 +1,66 @@
+use alloc::vec::Vec;
+
+use crate::AtomicSlice;
+
+/// A handle which keeps its own copy of the data of an `AtomicSlice` and
+/// only copies it again when a newer version was published. The copy can be
+/// borrowed for as long as needed without holding any read lock, and when
+/// nothing changed, bringing it up to date costs a single atomic load.
+/// Created using `AtomicSlice::cached_reader`.
+///
+/// ```
+/// use atomicslice::AtomicSlice;
+///
+/// let atomic_slice = AtomicSlice::new(vec![1, 2, 3]);
+/// let mut reader = atomic_slice.cached_reader();
+/// assert_eq!(reader.latest(), [1, 2, 3]);
+///
+/// atomic_slice.write(&[4, 5, 6]);
+/// assert_eq!(reader.cached(), [1, 2, 3]);
+/// assert_eq!(reader.latest(), [4, 5, 6]);
+/// ```
+pub struct CachedReader<'a, T, const BUFS: usize = 2> {
+    atomic_slice: &'a AtomicSlice<T, BUFS>,
+    /// The copy of the data at `version`
+    data: Vec<T>,
+    version: u64,
+}
+
+impl<T: Clone, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Create a handle which keeps its own copy of the data up to date. See
+    /// `CachedReader` for details.
+    pub fn cached_reader(&self) -> CachedReader<'_, T, BUFS> {
+        let guard = self.read();
+        CachedReader {
+            atomic_slice: self,
+            data: guard.to_vec(),
+            version: guard.version(),
+        }
+    }
+}
+
+impl<'a, T: Clone, const BUFS: usize> CachedReader<'a, T, BUFS> {
+    /// Bring the copy up to date if a newer version was published, and
+    /// return it.
+    pub fn latest(&mut self) -> &[T] {
+        self.atomic_slice
+            .copy_if_changed(&mut self.data, &mut self.version);
+        &self.data
+    }
+
+    /// Return the copy as it is, without checking for a newer version
+    pub fn cached(&self) -> &[T] {
+        &self.data
+    }
+
+    /// The version of the data that the copy holds. See
+    /// `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns true if a newer version than the copy's was published
+    pub fn is_stale(&self) -> bool {
+        self.atomic_slice.version() != self.version
+    }
+}
@@ -0,0 +1,150 @@
+//! `extern "C"` entry points for sharing an `AtomicSlice<f32>` with code
+//! written in C or any other language that can call C functions. Build the
+//! crate as a `staticlib` or `cdylib` with the `capi` feature to use these.
+//!
+//! C code sees the `AtomicSlice` only as an opaque pointer, which is created
+//! using `atomicslice_new` and destroyed using `atomicslice_free`. Reading
+//! works through an `AtomicSliceRead` which holds a read lock just like an
+//! `AtomicSliceReadGuard`, except that it must be released explicitly:
+//!
+//! ```c
+//! AtomicSliceRead read = atomicslice_read_begin(params);
+//! for (size_t i = 0; i < read.len; ++i) {
+//!     process(read.data[i]);
+//! }
+//! atomicslice_read_end(read);
+//! ```
+
+use alloc::boxed::Box;
+use core::{mem::ManuallyDrop, slice};
+
+use crate::{protocol::release, AtomicSlice};
+
+/// A read lock on the data of an `AtomicSlice<f32>`, returned by
+/// `atomicslice_read_begin`. The data stays valid and unchanged until the
+/// lock is released by passing this to `atomicslice_read_end`, which must
+/// happen exactly once. All fields must be left unchanged.
+#[repr(C)]
+pub struct AtomicSliceRead {
+    /// The first element of the locked data
+    pub data: *const f32,
+    /// The number of elements
+    pub len: usize,
+    /// The version of the locked data. See `AtomicSlice::version`.
+    pub version: u64,
+    /// The `AtomicSlice` the lock was acquired on
+    pub atomic_slice: *const AtomicSlice<f32>,
+    /// The partition being read from
+    pub slice: u8,
+}
+
+/// Create a new `AtomicSlice<f32>` holding a copy of the `len` elements
+/// pointed to by `data`. The returned pointer must eventually be passed to
+/// `atomicslice_free`.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable elements, or may be null if `len` is
+/// zero.
+#[no_mangle]
+pub unsafe extern "C" fn atomicslice_new(data: *const f32, len: usize) -> *mut AtomicSlice<f32> {
+    let data = if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(data, len)
+    };
+    Box::into_raw(Box::new(AtomicSlice::new(data.to_vec())))
+}
+
+/// Get the number of elements of an `AtomicSlice<f32>`.
+///
+/// # Safety
+///
+/// `atomic_slice` must have been created using `atomicslice_new` and not yet
+/// been freed.
+#[no_mangle]
+pub unsafe extern "C" fn atomicslice_len(atomic_slice: *const AtomicSlice<f32>) -> usize {
+    (*atomic_slice).len()
+}
+
+/// Get the version of the most recently published data of an
+/// `AtomicSlice<f32>`. See `AtomicSlice::version`.
+///
+/// # Safety
+///
+/// `atomic_slice` must have been created using `atomicslice_new` and not yet
+/// been freed.
+#[no_mangle]
+pub unsafe extern "C" fn atomicslice_version(atomic_slice: *const AtomicSlice<f32>) -> u64 {
+    (*atomic_slice).version()
+}
+
+/// Acquire a read lock on the data of an `AtomicSlice<f32>`, which must be
+/// released using `atomicslice_read_end`. See `AtomicSlice::read`.
+///
+/// # Safety
+///
+/// `atomic_slice` must have been created using `atomicslice_new` and must not
+/// be freed before the lock is released.
+#[no_mangle]
+pub unsafe extern "C" fn atomicslice_read_begin(
+    atomic_slice: *const AtomicSlice<f32>,
+) -> AtomicSliceRead {
+    // The lock is released by atomicslice_read_end instead
+    let guard = ManuallyDrop::new((*atomic_slice).read());
+    AtomicSliceRead {
+        data: guard.slice.as_ptr(),
+        len: guard.slice.len(),
+        version: guard.version,
+        atomic_slice,
+        slice: guard.current_slice,
+    }
+}
+
+/// Release a read lock acquired using `atomicslice_read_begin`.
+///
+/// # Safety
+///
+/// `read` must have been returned by `atomicslice_read_begin` and must not
+/// have been released already.
+#[no_mangle]
+pub unsafe extern "C" fn atomicslice_read_end(read: AtomicSliceRead) {
+    release::<2>((*read.atomic_slice).protocol.status(), read.slice);
+}
+
+/// Write `len` new elements to an `AtomicSlice<f32>`. Returns false without
+/// writing anything if `len` differs from the length of the `AtomicSlice`.
+/// See `AtomicSlice::write`.
+///
+/// # Safety
+///
+/// `atomic_slice` must have been created using `atomicslice_new` and not yet
+/// been freed. `data` must point to `len` readable elements, or may be null
+/// if `len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn atomicslice_write(
+    atomic_slice: *const AtomicSlice<f32>,
+    data: *const f32,
+    len: usize,
+) -> bool {
+    let data = if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(data, len)
+    };
+    (*atomic_slice).write_checked(data).is_ok()
+}
+
+/// Destroy an `AtomicSlice<f32>` created using `atomicslice_new`. Does
+/// nothing if `atomic_slice` is null.
+///
+/// # Safety
+///
+/// `atomic_slice` must have been created using `atomicslice_new`, must not
+/// have been freed already, and no read locks may be held on it.
+#[no_mangle]
+pub unsafe extern "C" fn atomicslice_free(atomic_slice: *mut AtomicSlice<f32>) {
+    if !atomic_slice.is_null() {
+        drop(Box::from_raw(atomic_slice));
+    }
+}
@@ -0,0 +1,172 @@
+//! Everything needed to read from an `AtomicSlice` in machine code that is
+//! generated at runtime, such as by Cranelift or LLVM, without calling back
+//! into Rust for every read. Unlike the rest of the crate's internals, the
+//! items in this module follow semantic versioning.
+//!
+//! The data of an `AtomicSlice<T, BUFS>` consists of `BUFS` partitions of
+//! `len()` elements each, which are stored one after the other starting at
+//! `data_ptr`, `pitch` elements apart. The pitch is `len()` unless the
+//! partitions are padded to be aligned, see `AtomicSlice::new_aligned`.
+//! Which partition readers should use and how many readers each partition
+//! has is kept in a single status word at `status_ptr`, whose bits are
+//! described by `StatusLayout`. The status word has the type `Status`,
+//! which is `u64` by default, `u32` with the `status-u32` feature and `u128`
+//! with the `status-u128` feature, and must only ever be accessed atomically.
+//!
+//! To read the data, generated code does the following, exactly like
+//! `AtomicSlice::read`:
+//!
+//! 1. Load the status word with relaxed ordering and take the index of the
+//!    active partition from its lowest bits using `StatusLayout::INDEX_MASK`.
+//! 2. Atomically add `StatusLayout::inc(index)` to the status word with
+//!    acquire ordering.
+//! 3. If the index in the value returned by the addition differs from the
+//!    one in step 1, or if that partition's use count was already at
+//!    `StatusLayout::MAX_COUNT`, atomically subtract the same amount with
+//!    relaxed ordering and call `acquire` instead, which returns the index of
+//!    a partition that it locked. A writer may have parked after seeing the
+//!    temporary use count, so if the value returned by the subtraction has
+//!    the `StatusLayout::WRITER_PARKED` bit set, call `wake_writers` before
+//!    calling `acquire`.
+//! 4. Read the partition starting at `partition_ptr(index)`.
+//! 5. Atomically subtract `StatusLayout::inc(index)` from the status word with
+//!    release ordering. If the value returned by the subtraction has the
+//!    `StatusLayout::WRITER_PARKED` bit set, call `wake_writers`.
+//!
+//! Instead of emitting these steps, generated code can also call `acquire`
+//! and `release`, which are plain `extern "C"` functions. A lock acquired
+//! through `acquire` should also be released through `release`, since debug
+//! builds keep track of the locks held by each thread.
+//!
+//! ```
+//! use atomicslice::{codegen, AtomicSlice};
+//!
+//! let atomic_slice = AtomicSlice::new(vec![1.0_f32, 2.0, 3.0]);
+//!
+//! // Pointers and functions to hand over to the generated code
+//! let status = codegen::status_ptr(&atomic_slice);
+//! let acquire: unsafe extern "C" fn(_) -> u8 = codegen::acquire::<2>;
+//! let release: unsafe extern "C" fn(_, u8) = codegen::release::<2>;
+//!
+//! unsafe {
+//!     let index = acquire(status);
+//!     let data = codegen::partition_ptr(&atomic_slice, index);
+//!     assert_eq!(*data.add(2), 3.0);
+//!     release(status, index);
+//! }
+//! ```
+
+use crate::{
+    protocol::{self, Protocol},
+    status::Layout,
+    sync::AtomicStatus,
+    AtomicSlice,
+};
+
+pub use crate::status::Status;
+
+/// The meaning of the bits of the status word of an `AtomicSlice` with
+/// `BUFS` partitions. The lowest bits hold the index of the partition that
+/// new readers should use, the bit above them is set while a writer waits
+/// for readers, and each partition's use count is kept in a field of its own
+/// in the upper bits.
+pub struct StatusLayout<const BUFS: usize>;
+
+impl<const BUFS: usize> StatusLayout<BUFS> {
+    /// The bits holding the index of the partition that new readers should use
+    pub const INDEX_MASK: Status = Layout::<BUFS>::INDEX_MASK;
+
+    /// Set while a writer is waiting for readers. Readers that see this bit
+    /// when releasing a partition must call `wake_writers`.
+    pub const WRITER_PARKED: Status = Layout::<BUFS>::WRITER_PARKED;
+
+    /// The largest use count that a single partition can hold
+    pub const MAX_COUNT: Status = Layout::<BUFS>::MAX_COUNT;
+
+    /// The mask of a single partition's use count field after shifting it
+    /// down by `count_shift`
+    pub const COUNT_MASK: Status = Layout::<BUFS>::FIELD_MASK;
+
+    /// The sum of `inc` for all partitions
+    pub const INC_ALL: Status = Layout::<BUFS>::INC_ALL;
+
+    /// All bits that may ever be set in the status word
+    pub const VALID_MASK: Status = Layout::<BUFS>::VALID_MASK;
+
+    /// The position of the lowest bit of the given partition's use count
+    pub const fn count_shift(slice: u8) -> u32 {
+        Layout::<BUFS>::FIRST_SHIFT + slice as u32 * Layout::<BUFS>::FIELD_BITS
+    }
+
+    /// The amount to add to the status word to increment the use count of
+    /// the given partition
+    pub const fn inc(slice: u8) -> Status {
+        1 << Self::count_shift(slice)
+    }
+}
+
+/// Get a pointer to the first element of the first partition
+pub fn data_ptr<T, const BUFS: usize>(atomic_slice: &AtomicSlice<T, BUFS>) -> *const T {
+    partition_ptr(atomic_slice, 0)
+}
+
+/// Get the number of elements from the start of one partition to the start
+/// of the next
+pub fn pitch<T, const BUFS: usize>(atomic_slice: &AtomicSlice<T, BUFS>) -> usize {
+    atomic_slice.pitch
+}
+
+/// Get a pointer to the first element of the given partition, which is
+/// `slice * pitch` elements past `data_ptr`. The data behind it may only be
+/// read while holding a read lock on the partition.
+pub fn partition_ptr<T, const BUFS: usize>(
+    atomic_slice: &AtomicSlice<T, BUFS>,
+    slice: u8,
+) -> *const T {
+    assert!((slice as usize) < BUFS);
+    atomic_slice
+        .data
+        .as_ptr()
+        .wrapping_add(slice as usize * atomic_slice.pitch)
+}
+
+/// Get a pointer to the status word. See the module documentation.
+pub fn status_ptr<T, const BUFS: usize>(atomic_slice: &AtomicSlice<T, BUFS>) -> *const Status {
+    atomic_slice.protocol.status() as *const AtomicStatus as *const Status
+}
+
+/// Acquire a read lock on the active partition and return its index, which
+/// must later be passed to `release`. Aborts the process if the partition
+/// already has as many readers as its use count can hold.
+///
+/// # Safety
+///
+/// `status` must come from `status_ptr` of an `AtomicSlice` with `BUFS`
+/// partitions that outlives the lock.
+pub unsafe extern "C" fn acquire<const BUFS: usize>(status: *const Status) -> u8 {
+    Protocol::<BUFS>::acquire_in(&*(status as *const AtomicStatus))
+}
+
+/// Release a read lock on the given partition.
+///
+/// # Safety
+///
+/// `status` must come from `status_ptr` of an `AtomicSlice` with `BUFS`
+/// partitions, on whose partition `slice` a read lock is held.
+pub unsafe extern "C" fn release<const BUFS: usize>(status: *const Status, slice: u8) {
+    protocol::release::<BUFS>(&*(status as *const AtomicStatus), slice);
+}
+
+/// Wake up the writers waiting for readers to release a partition, which
+/// generated code must call when it releases a partition and sees the
+/// `StatusLayout::WRITER_PARKED` bit.
+///
+/// # Safety
+///
+/// `status` must come from `status_ptr` of a live `AtomicSlice`.
+pub unsafe extern "C" fn wake_writers(status: *const Status) {
+    #[cfg(feature = "std")]
+    crate::park::unpark_all(status.addr());
+    #[cfg(not(feature = "std"))]
+    let _ = status;
+}
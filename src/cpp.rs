@@ -0,0 +1,134 @@
+//! A bridge built with the `cxx` crate, which exposes `AtomicSlice<float>`
+//! and `AtomicSlice<uint8_t>` to C++ as `atomicslice::AtomicSliceF32` and
+//! `atomicslice::AtomicSliceU8`. With the `cxx` feature, the build script
+//! compiles the C++ side of the bridge, and the header to include can be
+//! found at `target/cxxbridge/atomicslice/src/cpp.rs.h`.
+//!
+//! Each handle keeps the underlying `AtomicSlice` alive, so that every thread
+//! can own a handle of its own, created using `clone_handle`. Read lock
+//! guards are released when the `rust::Box` holding them goes out of scope:
+//!
+//! ```cpp
+//! float initial[2] = {0.0f, 0.0f};
+//! rust::Box<atomicslice::AtomicSliceF32> params =
+//!     atomicslice::new_atomic_slice_f32({initial, 2});
+//!
+//! {
+//!     rust::Box<atomicslice::AtomicSliceF32ReadGuard> guard = params->read();
+//!     for (float x : guard->data()) {
+//!         process(x);
+//!     }
+//! }
+//! ```
+//!
+//! Writing returns false instead of throwing if the new data has the wrong
+//! length, since audio engines are often built without exceptions.
+
+use alloc::{boxed::Box, sync::Arc};
+
+use crate::{ArcAtomicSliceReadGuard, AtomicSlice};
+
+#[cxx::bridge(namespace = "atomicslice")]
+mod ffi {
+    extern "Rust" {
+        type AtomicSliceF32;
+        type AtomicSliceF32ReadGuard;
+
+        fn new_atomic_slice_f32(data: &[f32]) -> Box<AtomicSliceF32>;
+        fn clone_handle(self: &AtomicSliceF32) -> Box<AtomicSliceF32>;
+        fn len(self: &AtomicSliceF32) -> usize;
+        fn version(self: &AtomicSliceF32) -> u64;
+        fn read(self: &AtomicSliceF32) -> Box<AtomicSliceF32ReadGuard>;
+        fn write(self: &AtomicSliceF32, data: &[f32]) -> bool;
+        fn data(self: &AtomicSliceF32ReadGuard) -> &[f32];
+        fn version(self: &AtomicSliceF32ReadGuard) -> u64;
+
+        type AtomicSliceU8;
+        type AtomicSliceU8ReadGuard;
+
+        fn new_atomic_slice_u8(data: &[u8]) -> Box<AtomicSliceU8>;
+        fn clone_handle(self: &AtomicSliceU8) -> Box<AtomicSliceU8>;
+        fn len(self: &AtomicSliceU8) -> usize;
+        fn version(self: &AtomicSliceU8) -> u64;
+        fn read(self: &AtomicSliceU8) -> Box<AtomicSliceU8ReadGuard>;
+        fn write(self: &AtomicSliceU8, data: &[u8]) -> bool;
+        fn data(self: &AtomicSliceU8ReadGuard) -> &[u8];
+        fn version(self: &AtomicSliceU8ReadGuard) -> u64;
+    }
+}
+
+/// Defines a handle and a read lock guard type for the given element type,
+/// along with the functions declared for them in the bridge
+macro_rules! bridge_types {
+    ($element:ty, $handle:ident, $guard:ident, $new:ident) => {
+        /// A reference-counted handle to an `AtomicSlice` for use from C++
+        pub struct $handle(Arc<AtomicSlice<$element>>);
+
+        /// A read lock guard for use from C++, created by `read`
+        pub struct $guard(ArcAtomicSliceReadGuard<$element>);
+
+        /// Create a new `AtomicSlice` holding a copy of the given data
+        pub fn $new(data: &[$element]) -> Box<$handle> {
+            Box::new($handle(Arc::new(AtomicSlice::new(data.to_vec()))))
+        }
+
+        impl $handle {
+            /// Create another handle to the same `AtomicSlice`
+            pub fn clone_handle(&self) -> Box<$handle> {
+                Box::new($handle(Arc::clone(&self.0)))
+            }
+
+            /// Get the number of elements. See `AtomicSlice::len`.
+            pub fn len(&self) -> usize {
+                self.0.len()
+            }
+
+            /// Returns true if the slice has no elements
+            pub fn is_empty(&self) -> bool {
+                self.0.is_empty()
+            }
+
+            /// Get the version of the most recently published data. See
+            /// `AtomicSlice::version`.
+            pub fn version(&self) -> u64 {
+                self.0.version()
+            }
+
+            /// Acquire a read lock on the slice. See `AtomicSlice::read`.
+            pub fn read(&self) -> Box<$guard> {
+                Box::new($guard(self.0.read_arc()))
+            }
+
+            /// Write new data, or return false if its length differs from
+            /// that of the `AtomicSlice`. See `AtomicSlice::write_checked`.
+            pub fn write(&self, data: &[$element]) -> bool {
+                self.0.write_checked(data).is_ok()
+            }
+        }
+
+        impl $guard {
+            /// Get the locked data
+            pub fn data(&self) -> &[$element] {
+                &self.0
+            }
+
+            /// The version of the locked data. See `AtomicSlice::version`.
+            pub fn version(&self) -> u64 {
+                self.0.version()
+            }
+        }
+    };
+}
+
+bridge_types!(
+    f32,
+    AtomicSliceF32,
+    AtomicSliceF32ReadGuard,
+    new_atomic_slice_f32
+);
+bridge_types!(
+    u8,
+    AtomicSliceU8,
+    AtomicSliceU8ReadGuard,
+    new_atomic_slice_u8
+);
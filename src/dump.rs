@@ -0,0 +1,65 @@
+use alloc::string::String;
+use core::fmt::{Debug, Write};
+
+use crate::{
+    status::{Layout, Status},
+    sync::Ordering,
+    AtomicSlice,
+};
+
+impl<T: Debug, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Describe the internal state of the `AtomicSlice` in a human-readable
+    /// form, for diagnosing problems such as a suspected torn read: the raw
+    /// status word and the fields it is made of, the version, and the
+    /// contents of every partition, labeled as active or stale along with
+    /// the version they hold and how many readers they have.
+    ///
+    /// Since stale partitions may be written to at any moment, this locks
+    /// out writers for as long as it takes, and may thus block like `write`.
+    ///
+    /// ```
+    /// use atomicslice::AtomicSlice;
+    ///
+    /// let atomic_slice = AtomicSlice::new(vec![1, 2, 3]);
+    /// atomic_slice.write(&[4, 5, 6]);
+    /// eprintln!("{}", atomic_slice.debug_dump());
+    /// ```
+    pub fn debug_dump(&self) -> String {
+        let _lock = self.protocol.lock_writer();
+
+        let status = self.protocol.status().load(Ordering::Relaxed);
+        let active = Layout::<BUFS>::active_slice(status);
+        let digits = Status::BITS as usize / 4;
+
+        let mut dump = String::new();
+        // Writing to a string never fails
+        let _ = writeln!(
+            dump,
+            "AtomicSlice with {} partitions of {} elements, {} elements apart",
+            BUFS, self.stride, self.pitch
+        );
+        let _ = writeln!(dump, "status word: {status:#0width$x}", width = digits + 2);
+        let _ = writeln!(dump, "  active partition: {active}");
+        let _ = writeln!(
+            dump,
+            "  writer parked: {}",
+            status & Layout::<BUFS>::WRITER_PARKED != 0
+        );
+        if !Layout::<BUFS>::valid(status) {
+            let _ = writeln!(dump, "  INVALID: bits outside of any field are set");
+        }
+        let _ = writeln!(dump, "version: {}", self.protocol.version());
+        for i in 0..BUFS as u8 {
+            let label = if i == active { "active" } else { "stale" };
+            // Safety: the write portion is locked, so that nothing is
+            // written to any partition while it is looked at
+            let (version, data) = unsafe { (self.protocol.slice_version(i), self.slice(i)) };
+            let _ = writeln!(
+                dump,
+                "partition {i} ({label}, version {version}, {} readers): {data:?}",
+                Layout::<BUFS>::use_count(i, status)
+            );
+        }
+        dump
+    }
+}
@@ -0,0 +1,246 @@
+use alloc::vec::Vec;
+use core::{
+    cell::UnsafeCell,
+    ops::Deref,
+    panic::{RefUnwindSafe, UnwindSafe},
+};
+
+use crate::{
+    protocol::{release, Protocol},
+    sync::AtomicStatus,
+    Backoff,
+};
+
+/// A group of slices of possibly different types which are always published
+/// together, so that readers never see some of them updated but not others.
+/// The group itself is a tuple of vectors, such as `(Vec<f32>, Vec<usize>)`,
+/// each of which keeps its length for the entire lifetime of the group.
+///
+/// ```
+/// use atomicslice::AtomicSliceGroup;
+///
+/// let filter = AtomicSliceGroup::new((vec![0.0_f32; 3], vec![0_usize; 2]));
+/// filter.write((&[0.5, 0.25, 0.125], &[64, 128]));
+/// let guard = filter.read();
+/// assert_eq!(guard.0, [0.5, 0.25, 0.125]);
+/// assert_eq!(guard.1, [64, 128]);
+/// ```
+pub struct AtomicSliceGroup<G, const BUFS: usize = 2> {
+    data: UnsafeCell<[G; BUFS]>,
+    protocol: Protocol<BUFS>,
+}
+
+/// A read lock guard on all slices of an `AtomicSliceGroup`, which
+/// dereferences to the tuple of vectors holding them.
+pub struct AtomicSliceGroupReadGuard<'a, G, const BUFS: usize = 2> {
+    group: &'a G,
+    current_slice: u8,
+    status: &'a AtomicStatus,
+    version: u64,
+}
+
+/// A tuple of vectors that can make up an `AtomicSliceGroup`. This is
+/// implemented for tuples of up to four vectors of cloneable elements.
+pub trait SliceGroup: Clone {
+    /// The tuple of shared slices of the vectors' elements
+    type Refs<'a>
+    where
+        Self: 'a;
+
+    /// The tuple of mutable slices of the vectors' elements
+    type Muts<'a>
+    where
+        Self: 'a;
+
+    /// Get shared access to the vectors' elements
+    fn as_refs(&self) -> Self::Refs<'_>;
+
+    /// Get mutable access to the vectors' elements, without being able to
+    /// change their lengths
+    fn as_muts(&mut self) -> Self::Muts<'_>;
+
+    /// Whether each of the given slices has the length of its vector
+    fn lengths_match(&self, refs: &Self::Refs<'_>) -> bool;
+
+    /// Clone the given slices into the vectors, which must have the same
+    /// lengths, see `lengths_match`
+    fn clone_from_refs(&mut self, refs: Self::Refs<'_>);
+}
+
+macro_rules! impl_slice_group {
+    ($($t:ident $i:tt),+) => {
+        impl<$($t: Clone),+> SliceGroup for ($(Vec<$t>,)+) {
+            type Refs<'a> = ($(&'a [$t],)+) where Self: 'a;
+            type Muts<'a> = ($(&'a mut [$t],)+) where Self: 'a;
+
+            fn as_refs(&self) -> Self::Refs<'_> {
+                ($(&self.$i[..],)+)
+            }
+
+            fn as_muts(&mut self) -> Self::Muts<'_> {
+                ($(&mut self.$i[..],)+)
+            }
+
+            fn lengths_match(&self, refs: &Self::Refs<'_>) -> bool {
+                $(refs.$i.len() == self.$i.len())&&+
+            }
+
+            fn clone_from_refs(&mut self, refs: Self::Refs<'_>) {
+                $(self.$i.clone_from_slice(refs.$i);)+
+            }
+        }
+    };
+}
+
+impl_slice_group!(A 0);
+impl_slice_group!(A 0, B 1);
+impl_slice_group!(A 0, B 1, C 2);
+impl_slice_group!(A 0, B 1, C 2, D 3);
+
+impl<G: SliceGroup> AtomicSliceGroup<G> {
+    /// Create a new `AtomicSliceGroup` from a tuple of vectors, which it
+    /// starts out with
+    pub fn new(group: G) -> AtomicSliceGroup<G> {
+        AtomicSliceGroup::new_with_buffers(group)
+    }
+}
+
+impl<G: SliceGroup, const BUFS: usize> AtomicSliceGroup<G, BUFS> {
+    /// Create a new `AtomicSliceGroup` with `BUFS` partitions. Otherwise
+    /// identical to `new`.
+    pub fn new_with_buffers(group: G) -> AtomicSliceGroup<G, BUFS> {
+        let protocol = Protocol::new();
+        AtomicSliceGroup {
+            data: UnsafeCell::new(core::array::from_fn(|_| group.clone())),
+            protocol,
+        }
+    }
+
+    /// Get the version of the most recently published data. See
+    /// `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.protocol.version()
+    }
+
+    /// Acquire a read lock on all slices at once, which are guaranteed to
+    /// have been published together. See `AtomicSlice::read`.
+    pub fn read(&self) -> AtomicSliceGroupReadGuard<'_, G, BUFS> {
+        let current_slice = self.protocol.acquire();
+        let group = unsafe { self.group(current_slice) };
+        let version = unsafe { self.protocol.slice_version(current_slice) };
+
+        AtomicSliceGroupReadGuard {
+            group,
+            current_slice,
+            status: self.protocol.status(),
+            version,
+        }
+    }
+
+    /// Block the current thread until a version newer than `since_version`
+    /// has been published. See `AtomicSlice::wait_for_update`.
+    #[cfg(feature = "std")]
+    pub fn wait_for_update(&self, since_version: u64) -> u64 {
+        self.protocol.wait_for_update(since_version)
+    }
+
+    /// Choose how writers wait. See `AtomicSlice::set_backoff`.
+    pub fn set_backoff(&mut self, backoff: Backoff) {
+        self.protocol.set_backoff(backoff);
+    }
+
+    /// Returns true if a thread panicked while writing. See
+    /// `AtomicSlice::is_poisoned`.
+    #[cfg(feature = "std")]
+    pub fn is_poisoned(&self) -> bool {
+        self.protocol.is_poisoned()
+    }
+
+    /// Clear the poisoned state after a panic while writing. See
+    /// `AtomicSlice::clear_poison`.
+    #[cfg(feature = "std")]
+    pub fn clear_poison(&self) {
+        self.protocol.clear_poison();
+    }
+
+    /// Write new data to all slices in place and publish them together. The
+    /// given function receives a tuple of mutable slices of the inactive
+    /// partition, which still hold stale data like with
+    /// `AtomicSlice::write_with`.
+    pub fn write_with<F: FnOnce(G::Muts<'_>)>(&self, f: F) {
+        let _lock = self.protocol.lock_writer();
+
+        // Wait to ensure the next group is not being used
+        let next_i = self.protocol.wait_for_free_slice();
+
+        // Let the caller fill in the next group
+        f(unsafe { self.group_mut(next_i) }.as_muts());
+
+        self.protocol.publish(next_i);
+    }
+
+    /// Write a tuple of slices of new data and publish them together. Each
+    /// slice must have the same length as the corresponding vector that the
+    /// group was created with, otherwise this method panics.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn write(&self, data: G::Refs<'_>) {
+        // Check before locking, so that panicking doesn't poison anything
+        if !self.read().lengths_match(&data) {
+            panic!("Attempted to write slice of the wrong length to AtomicSliceGroup");
+        }
+
+        let _lock = self.protocol.lock_writer();
+
+        let next_i = self.protocol.wait_for_free_slice();
+        unsafe { self.group_mut(next_i) }.clone_from_refs(data);
+
+        self.protocol.publish(next_i);
+    }
+
+    /// Get shared access to one of the groups.
+    ///
+    /// Safety: the group must not be written to while the returned reference
+    /// is alive, i.e. the caller must either hold a read lock on it or hold
+    /// exclusive access to the write portion.
+    unsafe fn group(&self, slice: u8) -> &G {
+        &*(self.data.get() as *const G).add(slice as usize)
+    }
+
+    /// Get mutable access to one of the groups.
+    ///
+    /// Safety: the caller must hold exclusive access to the write portion
+    /// and the group must not be in use by any readers.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn group_mut(&self, slice: u8) -> &mut G {
+        &mut *(self.data.get() as *mut G).add(slice as usize)
+    }
+}
+
+unsafe impl<G: Send + Sync, const BUFS: usize> Sync for AtomicSliceGroup<G, BUFS> {}
+unsafe impl<G: Send, const BUFS: usize> Send for AtomicSliceGroup<G, BUFS> {}
+impl<G: RefUnwindSafe, const BUFS: usize> RefUnwindSafe for AtomicSliceGroup<G, BUFS> {}
+impl<G: UnwindSafe, const BUFS: usize> UnwindSafe for AtomicSliceGroup<G, BUFS> {}
+
+impl<'a, G, const BUFS: usize> Deref for AtomicSliceGroupReadGuard<'a, G, BUFS> {
+    type Target = G;
+
+    fn deref(&self) -> &G {
+        self.group
+    }
+}
+
+impl<'a, G, const BUFS: usize> Drop for AtomicSliceGroupReadGuard<'a, G, BUFS> {
+    fn drop(&mut self) {
+        release::<BUFS>(self.status, self.current_slice);
+    }
+}
+
+impl<'a, G, const BUFS: usize> AtomicSliceGroupReadGuard<'a, G, BUFS> {
+    /// The version of the data that this guard is holding onto.
+    /// See `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+}
@@ -0,0 +1,205 @@
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+use alloc::{
+    alloc::{alloc, handle_alloc_error, Layout},
+    boxed::Box,
+    vec::Vec,
+};
+use core::{
+    cell::UnsafeCell,
+    panic::{RefUnwindSafe, UnwindSafe},
+    ptr,
+};
+
+use crate::{protocol::Protocol, AtomicSliceReadGuard, Backoff, TryWriteError};
+
+/// A dynamically-sized variant of `AtomicSlice` which stores its data
+/// directly inside itself rather than in a separate boxed slice. Since it is
+/// unsized, it can only be created behind a pointer, using either
+/// `AtomicSliceInline::new_boxed` or `AtomicSliceInline::new_arc`.
+///
+/// Reads don't need to follow an additional pointer to reach the data, which
+/// begins right after the cache lines holding the status word and the rest of
+/// the synchronization state. The API is otherwise the same as that of an
+/// `AtomicSlice` with two partitions.
+#[repr(C)]
+pub struct AtomicSliceInline<T> {
+    protocol: Protocol<2>,
+    /// Both partitions, one after the other
+    data: UnsafeCell<[T]>,
+}
+
+impl<T: Clone> AtomicSliceInline<T> {
+    /// Create a new boxed `AtomicSliceInline` from a vector of data. The
+    /// `AtomicSliceInline` will have the length of this vector for its
+    /// entire lifetime.
+    pub fn new_boxed(mut data: Vec<T>) -> Box<AtomicSliceInline<T>> {
+        let stride = data.len();
+        // Fill the other partition with a copy of the initial data. This is
+        // done up front so that a panicking clone can't leak anything.
+        data.extend_from_within(..stride);
+
+        let (layout, data_offset) = Layout::new::<Protocol<2>>()
+            .extend(Layout::array::<T>(data.len()).unwrap())
+            .unwrap();
+        let layout = layout.pad_to_align();
+
+        unsafe {
+            let raw = alloc(layout);
+            if raw.is_null() {
+                handle_alloc_error(layout);
+            }
+            ptr::write(raw as *mut Protocol<2>, Protocol::new());
+            ptr::copy_nonoverlapping(data.as_ptr(), raw.add(data_offset) as *mut T, data.len());
+            // The elements were moved out, only the vector's buffer remains
+            let len = data.len();
+            data.set_len(0);
+
+            // The pointer metadata of a slice becomes that of the struct
+            // whose last field is the slice
+            let fat =
+                ptr::slice_from_raw_parts_mut(raw as *mut T, len) as *mut AtomicSliceInline<T>;
+            Box::from_raw(fat)
+        }
+    }
+
+    /// Create a new reference-counted `AtomicSliceInline` from a vector of
+    /// data. See `new_boxed`.
+    #[cfg(target_has_atomic = "ptr")]
+    pub fn new_arc(data: Vec<T>) -> Arc<AtomicSliceInline<T>> {
+        Arc::from(AtomicSliceInline::new_boxed(data))
+    }
+
+    /// Write a slice of new data. The given slice must have the same length as
+    /// the `AtomicSliceInline` itself, otherwise this method panics.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn write(&self, data: &[T]) {
+        if data.len() != self.len() {
+            panic!("Attempted to write slice of the wrong length to AtomicSliceInline");
+        }
+
+        self.write_with(|slice| slice.clone_from_slice(data));
+    }
+
+    /// Attempt to write a slice of new data without waiting. See
+    /// `AtomicSlice::try_write`.
+    pub fn try_write(&self, data: &[T]) -> Result<(), TryWriteError> {
+        if data.len() != self.len() {
+            panic!("Attempted to write slice of the wrong length to AtomicSliceInline");
+        }
+
+        let Some(_lock) = self.protocol.try_lock_writer() else {
+            return Err(TryWriteError::WriteInProgress);
+        };
+
+        let Some(next_i) = self.protocol.free_slice() else {
+            return Err(TryWriteError::ReadInProgress);
+        };
+
+        unsafe { self.slice_mut(next_i) }.clone_from_slice(data);
+
+        self.protocol.publish(next_i);
+        Ok(())
+    }
+}
+
+impl<T> AtomicSliceInline<T> {
+    /// Get the number of elements
+    pub fn len(&self) -> usize {
+        // The stored data holds both partitions
+        self.data.get().len() / 2
+    }
+
+    /// Returns true if the slice has no elements
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the version of the most recently published data. See
+    /// `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.protocol.version()
+    }
+
+    /// Acquire a read lock on the slice. See `AtomicSlice::read`.
+    pub fn read(&self) -> AtomicSliceReadGuard<'_, T> {
+        let current_slice = self.protocol.acquire();
+        let slice: &[T] = unsafe { self.slice(current_slice) };
+        let version = unsafe { self.protocol.slice_version(current_slice) };
+
+        AtomicSliceReadGuard {
+            slice,
+            current_slice,
+            status: self.protocol.status(),
+            version,
+        }
+    }
+
+    /// Block the current thread until a version newer than `since_version`
+    /// has been published. See `AtomicSlice::wait_for_update`.
+    #[cfg(feature = "std")]
+    pub fn wait_for_update(&self, since_version: u64) -> u64 {
+        self.protocol.wait_for_update(since_version)
+    }
+
+    /// Choose how writers wait. See `AtomicSlice::set_backoff`.
+    pub fn set_backoff(&mut self, backoff: Backoff) {
+        self.protocol.set_backoff(backoff);
+    }
+
+    /// Returns true if a thread panicked while writing. See
+    /// `AtomicSlice::is_poisoned`.
+    #[cfg(feature = "std")]
+    pub fn is_poisoned(&self) -> bool {
+        self.protocol.is_poisoned()
+    }
+
+    /// Clear the poisoned state after a panic while writing. See
+    /// `AtomicSlice::clear_poison`.
+    #[cfg(feature = "std")]
+    pub fn clear_poison(&self) {
+        self.protocol.clear_poison();
+    }
+
+    /// Write new data in place. See `AtomicSlice::write_with`.
+    pub fn write_with<F: FnOnce(&mut [T])>(&self, f: F) {
+        let _lock = self.protocol.lock_writer();
+
+        // Wait to ensure the next slice is not being used
+        let next_i = self.protocol.wait_for_free_slice();
+
+        // Let the caller fill in the next slice
+        f(unsafe { self.slice_mut(next_i) });
+
+        self.protocol.publish(next_i);
+    }
+
+    /// Get shared access to one of the slices.
+    ///
+    /// Safety: the slice must not be written to while the returned reference
+    /// is alive, i.e. the caller must either hold a read lock on it or hold
+    /// exclusive access to the write portion.
+    unsafe fn slice(&self, slice: u8) -> &[T] {
+        let stride = self.len();
+        let ptr_begin = (self.data.get() as *const T).add(slice as usize * stride);
+        core::slice::from_raw_parts(ptr_begin, stride)
+    }
+
+    /// Get mutable access to one of the slices.
+    ///
+    /// Safety: the caller must hold exclusive access to the write portion
+    /// and the slice must not be in use by any readers.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn slice_mut(&self, slice: u8) -> &mut [T] {
+        let stride = self.len();
+        let ptr_begin = (self.data.get() as *mut T).add(slice as usize * stride);
+        core::slice::from_raw_parts_mut(ptr_begin, stride)
+    }
+}
+
+unsafe impl<T: Send + Sync> Sync for AtomicSliceInline<T> {}
+unsafe impl<T: Send> Send for AtomicSliceInline<T> {}
+impl<T: RefUnwindSafe> RefUnwindSafe for AtomicSliceInline<T> {}
+impl<T: UnwindSafe> UnwindSafe for AtomicSliceInline<T> {}
@@ -0,0 +1,123 @@
+use std::io;
+
+use crate::{protocol::WriteLock, AtomicSlice, AtomicSliceReadGuard};
+
+/// An `std::io::Write` implementation which writes bytes straight into the
+/// inactive partition of an `AtomicSlice<u8>` and publishes them on every
+/// call to `flush`. Created using `AtomicSlice::writer`.
+///
+/// Each batch of bytes between flushes is written from the start of the
+/// slice. Like with `AtomicSlice::write_with`, any bytes past the last one
+/// written keep whatever data the partition held before, and writing more
+/// bytes than fit into the slice fails with `ErrorKind::WriteZero`. Bytes
+/// that haven't been flushed yet are discarded when the writer is dropped.
+///
+/// Other writers are locked out for as long as this writer is alive.
+pub struct ByteWriter<'a, const BUFS: usize = 2> {
+    atomic_slice: &'a AtomicSlice<u8, BUFS>,
+    _lock: WriteLock<'a, BUFS, 1>,
+    /// The slice being written to, if any bytes were written since the last
+    /// flush, and how many
+    slice: Option<(u8, usize)>,
+}
+
+/// An `std::io::Read` and `std::io::BufRead` implementation which reads
+/// the bytes of an `AtomicSlice<u8>` from start to end, directly out of a
+/// read lock on the published data. Created using `AtomicSlice::reader`.
+///
+/// The read lock is held for as long as this reader is alive, so all bytes
+/// come from the same version of the data.
+pub struct ByteReader<'a, const BUFS: usize = 2> {
+    guard: AtomicSliceReadGuard<'a, u8, BUFS>,
+    position: usize,
+}
+
+impl<const BUFS: usize> AtomicSlice<u8, BUFS> {
+    /// Acquire a read lock on the slice and create a reader of its bytes,
+    /// which lets anything that consumes bytes, such as a deserializer,
+    /// read from the `AtomicSlice` without copying the data first. See
+    /// `ByteReader` for details.
+    pub fn reader(&self) -> ByteReader<'_, BUFS> {
+        ByteReader {
+            guard: self.read(),
+            position: 0,
+        }
+    }
+
+    /// Create a writer which lets anything that produces bytes, such as a
+    /// serializer, write into the `AtomicSlice` without any intermediate
+    /// buffer. See `ByteWriter` for details.
+    ///
+    /// This method may block if other threads are writing, and writing to
+    /// the returned writer may block if any readers are holding lock guards
+    /// for extended periods of time.
+    pub fn writer(&self) -> ByteWriter<'_, BUFS> {
+        ByteWriter {
+            atomic_slice: self,
+            _lock: self.protocol.lock_writer(),
+            slice: None,
+        }
+    }
+}
+
+impl<'a, const BUFS: usize> ByteReader<'a, BUFS> {
+    /// The version of the data being read. See `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.guard.version()
+    }
+
+    /// The number of bytes read so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Stop reading and get back the read lock guard
+    pub fn into_inner(self) -> AtomicSliceReadGuard<'a, u8, BUFS> {
+        self.guard
+    }
+}
+
+impl<'a, const BUFS: usize> io::Read for ByteReader<'a, BUFS> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = io::Read::read(&mut &self.guard[self.position..], buf)?;
+        self.position += n;
+        Ok(n)
+    }
+}
+
+impl<'a, const BUFS: usize> io::BufRead for ByteReader<'a, BUFS> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(&self.guard[self.position..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.position = (self.position + amt).min(self.guard.len());
+    }
+}
+
+impl<'a, const BUFS: usize> io::Write for ByteWriter<'a, BUFS> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let (next_i, position) = *self
+            .slice
+            .get_or_insert_with(|| (self.atomic_slice.protocol.wait_for_free_slice(), 0));
+
+        // Safety: the write portion is locked and the slice is not in use
+        let slice = unsafe { self.atomic_slice.slice_mut(next_i) };
+        let n = buf.len().min(slice.len() - position);
+        slice[position..position + n].copy_from_slice(&buf[..n]);
+
+        self.slice = Some((next_i, position + n));
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some((next_i, _)) = self.slice.take() {
+            self.atomic_slice.protocol.publish(next_i);
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 //! `AtomicSlice<T>` is a thread-safe wrapper around an array of arbitrary data which
 //! is just about as fast as possible to read while still being writable. Reading from
-//! an `AtomicSlice<T>` involves exactly three atomic operations (in release builds).
+//! an `AtomicSlice<T>` usually involves an atomic load and only two atomic read-modify-write
+//! operations (in release builds), and never more than five atomic operations.
 //! Writing may involve some locking but is also possible from multiple threads.
 //!
 //! `AtomicSlice<T>` is thus heavily optimized for the case of frequent reads from
@@ -14,18 +15,224 @@
 //! the two partitions switch roles and new readers being accessing the freshly-written
 //! data immediately, while existing readers guard access to the stale data until they
 //! are dropped.
+//!
+//! Optionally, `AtomicSlice<T, BUFS>` can use a pool of more than two partitions, in
+//! which case writers can choose any partition that isn't being read from and are thus
+//! much less likely to ever wait for readers.
+//!
+//! The `std` feature is enabled by default. Without it, the crate only depends
+//! on `core` and `alloc`, which makes it usable on bare-metal targets, minus
+//! the methods that block the thread while waiting such as `wait_for_update`.
+//! On targets without native 64-bit atomics, the `portable-atomic` feature
+//! provides them through the `portable-atomic` crate instead, which may need
+//! further configuration on targets without any compare-and-swap operations
+//! such as `thumbv6m-none-eabi` (see its documentation). Anything that
+//! involves an `Arc`, such as `read_arc` and `split`, is only available on
+//! targets that support atomic pointer-sized compare-and-swap.
+//!
+//! Where 64-bit atomics are only emulated, such as through `portable-atomic`
+//! on 32-bit targets, the `status-u32` feature switches to a 32-bit status
+//! word so that `read` keeps relying on native atomics only. The version
+//! counter stays 64 bits wide, but is only touched by writers and by methods
+//! that look at versions, such as `read_if_newer`. Conversely, the
+//! `status-u128` feature switches to a 128-bit status word for the sake of
+//! much higher limits on the number of simultaneous reads, which is best used
+//! on targets with native 128-bit atomics such as `x86_64` with `cmpxchg16b`.
+//!
+//! Debug builds check every status word that readers and writers come across
+//! for signs of corruption, such as a use count dropping below zero or bits
+//! being set that no partition owns. The `paranoid` feature keeps these checks
+//! in release builds, which then panic instead of silently reading the wrong
+//! partition. This is mostly useful when generated code or C code takes part
+//! in the protocol through the `codegen` module or `RawReadLock`. When
+//! something does go wrong, the `debug-dump` feature adds
+//! `AtomicSlice::debug_dump`, which spells out the fields of the status word
+//! along with the contents of every partition.
+//!
+//! On `wasm32-unknown-unknown`, an `AtomicSlice` in shared memory can be used
+//! from several threads, such as the main thread and an audio worklet, when
+//! building with the `atomics` and `bulk-memory` target features, which
+//! provide native 64-bit atomics. Without them, there is only a single
+//! thread and the atomics are ordinary memory accesses. Reading and writing
+//! keep working, but anything that would have to wait for another thread,
+//! such as a write that needs the partition the thread itself is reading
+//! from, or `wait_for_update`, panics instead of waiting forever. Awaiting
+//! `changed` still works, since other tasks may write in the meantime.
+//!
+//! Browsers don't allow the main thread to block. `Backoff::Park` and
+//! `wait_for_update` put the thread to sleep using `memory.atomic.wait32`,
+//! which traps on the main thread, so it should only write using a spinning
+//! `Backoff` such as the default, which can't yield to anything there, or
+//! without waiting using `try_write`. `write_timeout` isn't available since
+//! there is no clock.
+//!
+//! With the `loom` feature, all atomics come from the `loom` crate, so that
+//! code built on `AtomicSlice` can be model-checked along with the crate's
+//! own protocol by running it inside `loom::model`, and can't be used
+//! anywhere else. `AtomicArray` and the `codegen` module aren't available
+//! then, `Backoff::Park` only yields to other threads instead of parking,
+//! and methods that block the thread such as `wait_for_update` must not be
+//! called, since loom can't see what they wait for.
+//!
+//! Similarly, with the `shuttle` feature, all atomics come from the `shuttle`
+//! crate, whose scheduler then decides which thread runs at every atomic
+//! operation and every iteration of a spinning writer. A schedule that
+//! triggers a bug can be reproduced using the seed of a `RandomScheduler` or
+//! `shuttle::replay`. Shuttle only models sequentially consistent atomics,
+//! so it finds bugs caused by unlucky interleavings rather than by weak
+//! memory orderings, which is what loom is for. Again, `codegen` isn't
+//! available, `Backoff::Park` only yields, and `wait_for_update` must not be
+//! called.
 
-#[cfg(test)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(all(feature = "status-u32", feature = "status-u128"))]
+compile_error!("The status-u32 and status-u128 features are mutually exclusive");
+
+#[cfg(all(loom, shuttle))]
+compile_error!("The loom and shuttle features are mutually exclusive");
+
+/// Assert that the protocol's invariants hold, like `debug_assert!`, except
+/// that the `paranoid` feature keeps checking them in release builds
+macro_rules! paranoid_assert {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "paranoid")]
+        assert!($($arg)*);
+        #[cfg(not(feature = "paranoid"))]
+        debug_assert!($($arg)*);
+    };
+}
+
+mod align;
+#[cfg(feature = "allocator-api2")]
+mod allocator;
+#[cfg(target_has_atomic = "ptr")]
+mod arc;
+#[cfg(feature = "rkyv")]
+mod archive;
+// Loom's atomics can't be created in constant contexts
+#[cfg(not(loom))]
+mod array;
+mod backoff;
+mod bank;
+#[cfg(feature = "bytes")]
+mod bytes;
+mod cached;
+#[cfg(feature = "capi")]
+pub mod capi;
+// Neither loom's nor shuttle's atomics have the layout of plain integers
+#[cfg(not(any(loom, shuttle)))]
+pub mod codegen;
+#[cfg(feature = "cxx")]
+pub mod cpp;
+#[cfg(feature = "debug-dump")]
+mod dump;
+mod group;
+mod inline;
+#[cfg(feature = "std")]
+mod io;
+#[cfg(all(test, loom))]
+mod loom_test;
+mod matrix;
+#[cfg(feature = "std")]
+mod memory;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "std")]
+mod notify;
+#[cfg(feature = "std")]
+mod park;
+#[cfg(feature = "bytemuck")]
+mod pod;
+mod protocol;
+#[cfg(all(debug_assertions, feature = "std", not(any(loom, shuttle))))]
+mod registry;
+#[cfg(feature = "zeroize")]
+mod scrub;
+#[cfg(feature = "serde")]
+mod serialize;
+mod sharded;
+#[cfg(all(test, shuttle))]
+mod shuttle_test;
+#[cfg(target_has_atomic = "ptr")]
+mod snapshot;
+#[cfg(target_has_atomic = "ptr")]
+mod split;
+mod status;
+mod storage;
+#[cfg(feature = "stream")]
+mod stream;
+mod sync;
+#[cfg(all(test, not(any(loom, shuttle))))]
 mod test;
+mod uninit;
+mod value;
+mod vec;
+mod watch;
+#[cfg(feature = "zerocopy")]
+mod zerocopy_impl;
+
+#[cfg(target_has_atomic = "ptr")]
+pub use arc::AtomicArcSlice;
+#[cfg(not(loom))]
+pub use array::AtomicArray;
+pub use backoff::Backoff;
+pub use bank::{AtomicSliceBank, AtomicSliceBankReadGuard};
+pub use cached::CachedReader;
+pub use group::{AtomicSliceGroup, AtomicSliceGroupReadGuard, SliceGroup};
+pub use inline::AtomicSliceInline;
+#[cfg(feature = "std")]
+pub use io::{ByteReader, ByteWriter};
+pub use matrix::{AtomicSlice2D, AtomicSlice2DReadGuard};
+#[cfg(feature = "std")]
+pub use memory::MemoryOptions;
+#[cfg(feature = "metrics")]
+pub use metrics::{WaitStats, WriteStats};
+#[cfg(feature = "zeroize")]
+pub use scrub::ZeroizingAtomicSlice;
+pub use sharded::ShardedAtomicSlice;
+#[cfg(target_has_atomic = "ptr")]
+pub use split::{Reader, Writer};
+#[cfg(feature = "stream")]
+pub use stream::Subscription;
+pub use uninit::UninitAtomicSlice;
+pub use value::{AtomicValue, AtomicValueReadGuard};
+pub use vec::AtomicVec;
+pub use watch::RangeSubscription;
 
-use std::{
-    cell::UnsafeCell,
-    ops::Deref,
-    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+use alloc::{boxed::Box, vec::Vec};
+#[cfg(target_has_atomic = "ptr")]
+use core::marker::PhantomData;
+use core::{
+    mem::ManuallyDrop,
+    ops::{Deref, RangeBounds},
+    panic::{RefUnwindSafe, UnwindSafe},
 };
+use protocol::{release, retain, Protocol};
+#[cfg(target_has_atomic = "ptr")]
+use snapshot::SnapshotCache;
+use storage::Storage;
+use sync::AtomicStatus;
 
-// Status 64-bit layout
-// Byte 0 : active slice index
+#[cfg(all(
+    feature = "std",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "async")]
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+// Status 64-bit layout (with the default of two slices)
+// Byte 0 : active slice index, and whether a writer is parked (highest bit)
 // Byte 1 : unused padding
 // Byte 2 : slice 1 use count, low byte
 // Byte 3 : slice 1 use count, high byte
@@ -34,10 +241,19 @@ use std::{
 // Byte 6 : slice 2 use count, high byte
 // Byte 7 : unused padding
 // This provides 1 byte for active slice, and 2 bytes for each slice's
-// use count. More slices could be accommodated by trading off the
-// maximum number of simultaneous reads and the amount of padding.
+// use count. More slices are accommodated by trading off the maximum
+// number of simultaneous reads and the amount of padding, see `Layout`.
+// With the `status-u32` feature, the status is only 32 bits wide, with
+// just enough bits for the active slice index and the rest split evenly
+// between the use counts. With the `status-u128` feature, the status is
+// 128 bits wide and provides 4 bytes for each of up to three slices' use
+// counts.
 
+// Superseded by `codegen::StatusLayout`, which also covers other numbers of
+// slices and status word widths
 #[doc(hidden)]
+#[allow(clippy::unusual_byte_groupings)]
+#[cfg(not(any(feature = "status-u32", feature = "status-u128")))]
 pub mod constants {
     pub const CURRENT_SLICE_MASK: u64 = 0x1;
 
@@ -46,50 +262,96 @@ pub mod constants {
 
     pub const VALID_STATUS_MASK: u64 = 0x00_FFFF_00_FFFF_00_01;
 
-    pub const INC_ALL_SLICES: u64 = SLICE_1_INC | SLICE_2_INC;
-}
-
-fn slice_1_use_count(status: u64) -> u16 {
-    ((status >> 16) & 0xFFFF) as u16
-}
-
-fn slice_2_use_count(status: u64) -> u16 {
-    ((status >> 40) & 0xFFFF) as u16
-}
+    pub const WRITER_PARKED: u64 = 0x80;
 
-fn slice_use_count(slice: u8, status: u64) -> u16 {
-    match slice {
-        0 => slice_1_use_count(status),
-        1 => slice_2_use_count(status),
-        _ => panic!("Invalid slice index"),
-    }
-}
-
-fn valid_status(status: u64) -> bool {
-    (status & !constants::VALID_STATUS_MASK) == 0
+    pub const INC_ALL_SLICES: u64 = SLICE_1_INC | SLICE_2_INC;
 }
 
 /// A slice of data that can be written and read from multiple threads,
 /// which is heavily optimized for multiple concurrent reads and occasional
 /// writes.
 ///
-/// Reading from the slice involves only three atomic operations in total
-/// (when compiled in release mode). Writing the data involves some locking
-/// and is thus slower.
+/// Reading from the slice usually involves only an atomic load and two
+/// atomic read-modify-write operations in total (when compiled in release
+/// mode). Writing the data involves some locking and is thus slower.
 ///
 /// Internally, `AtomicSlice` allocates twice as much space as requested
 /// during construction, and readers and writers switch back and forth
 /// between accessing two partitions.
 ///
-/// Currently, the data is stored indirectly in a boxed slice. In the future,
-/// it may be stored directly within the `AtomicSlice` which would then
-/// become a dynamically-sized type, giving more control to the user over the
-/// amount of indirection involved.
-pub struct AtomicSlice<T> {
-    data: UnsafeCell<Box<[T]>>,
+/// More partitions can be requested through the `BUFS` parameter, which
+/// must be between 2 and 8. With more than two partitions, a writer
+/// can pick any partition that no readers are holding onto, so writers
+/// essentially never need to wait for lingering readers. The trade-off is
+/// memory usage and a lower limit on the number of simultaneous reads,
+/// which ranges from 65535 with two or three partitions down to 63 with
+/// eight partitions. With the `status-u32` feature, which keeps reads
+/// lock-free on targets that only have native 32-bit atomics, these limits
+/// are lower still, from 16383 with two partitions down to 3 with eight.
+/// With the `status-u128` feature, they are raised to over four billion with
+/// two or three partitions and 16383 with eight.
+///
+/// The data is stored indirectly in a single heap allocation, which can come
+/// from a custom allocator using `new_in` with the `allocator-api2` feature.
+/// To avoid this extra indirection, `AtomicSliceInline` stores the data directly within itself
+/// as a dynamically-sized type. For data whose length is known at compile
+/// time, `AtomicArray` stores everything inline and can even be a `static`.
+/// A single value rather than a slice can be shared using `AtomicValue`,
+/// data whose length changes over time using `AtomicVec`, matrices using
+/// `AtomicSlice2D`, and several channels that are published together using
+/// `AtomicSliceBank`. Slices of different types that must be published
+/// together can be grouped using `AtomicSliceGroup`.
+///
+/// Since readers on different threads get shared references to the same
+/// elements, an `AtomicSlice<T>` can only be shared between threads if `T`
+/// is `Sync`, and since writers may clone and drop elements on any thread,
+/// only if `T` is `Send` as well:
+///
+/// ```compile_fail
+/// use atomicslice::AtomicSlice;
+/// use std::cell::Cell;
+///
+/// fn assert_sync<T: Sync>(_: &T) {}
+///
+/// let atomic_slice = AtomicSlice::new(vec![Cell::new(0_u32); 4]);
+/// assert_sync(&atomic_slice);
+/// ```
+///
+/// ```compile_fail
+/// use atomicslice::AtomicSlice;
+/// use std::marker::PhantomData;
+///
+/// fn assert_sync<T: Sync>(_: &T) {}
+///
+/// // Sync but not Send
+/// #[derive(Clone)]
+/// struct ThreadBound(PhantomData<*const ()>);
+/// unsafe impl Sync for ThreadBound {}
+///
+/// let atomic_slice = AtomicSlice::new(vec![ThreadBound(PhantomData)]);
+/// assert_sync(&atomic_slice);
+/// ```
+///
+/// A panic while writing, such as from cloning one of the elements, unlocks
+/// the write portion again without publishing anything, so readers keep
+/// seeing the most recently published data. An `AtomicSlice` is thus
+/// `UnwindSafe` and `RefUnwindSafe` whenever `T` is, and can be shared with
+/// code running inside `catch_unwind` and used as usual after it panicked.
+pub struct AtomicSlice<T, const BUFS: usize = 2> {
+    data: Storage<T>,
+    /// The number of elements of each partition
     stride: usize,
-    status: AtomicU64,
-    currently_writing: AtomicBool,
+    /// The number of elements from the start of one partition to the start
+    /// of the next, which is more than `stride` if partitions are padded to
+    /// be aligned, see `new_aligned`
+    pitch: usize,
+    protocol: Protocol<BUFS>,
+    /// Whether the data was locked into memory, see `MemoryOptions`
+    #[cfg(all(feature = "mlock", unix))]
+    locked: bool,
+    /// The snapshot most recently handed out by `snapshot_arc`
+    #[cfg(target_has_atomic = "ptr")]
+    snapshot: SnapshotCache<T>,
 }
 
 /// A smart pointer type representing read-only access to the data in an
@@ -97,152 +359,1174 @@ pub struct AtomicSlice<T> {
 /// lock on the `AtomicSlice`. In situations of high load where write
 /// throughput is also important, this lock should ideally not be held
 /// for very long.
-pub struct AtomicSliceReadGuard<'a, T> {
+///
+/// Like a shared reference to the data, a guard can be sent to and shared
+/// with other threads, such as scoped threads or parallel iterators, as long
+/// as `T` is `Sync`. Read locks may be released by a different thread than
+/// the one that acquired them.
+pub struct AtomicSliceReadGuard<'a, T, const BUFS: usize = 2> {
     slice: &'a [T],
     current_slice: u8,
-    status: &'a AtomicU64,
+    status: &'a AtomicStatus,
+    version: u64,
 }
 
-impl<T: Default + Clone> AtomicSlice<T> {
-    /// Create a new `AtomicSlice` from a vector of data. The `AtomicSlice`
-    /// will have the length of this vector for its entire lifetime.
-    pub fn new(mut data: Vec<T>) -> AtomicSlice<T> {
-        let stride = data.len();
-        data.resize(stride * 2, T::default());
-        data.shrink_to_fit();
-        AtomicSlice {
-            data: UnsafeCell::new(data.into_boxed_slice()),
-            stride,
-            status: AtomicU64::new(0),
-            currently_writing: AtomicBool::new(false),
+/// A read lock guard which gives access to only part of the data in an
+/// `AtomicSlice`, or to data derived from it, while keeping the read lock
+/// held. Created using `AtomicSliceReadGuard::map`.
+pub struct MappedAtomicSliceReadGuard<'a, T, const BUFS: usize = 2> {
+    slice: &'a [T],
+    current_slice: u8,
+    status: &'a AtomicStatus,
+    version: u64,
+}
+
+/// A read lock guard just like `AtomicSliceReadGuard`, except that it holds
+/// its own reference-counted pointer to the `AtomicSlice` instead of
+/// borrowing it. This allows it to be sent to other threads or stored for
+/// arbitrarily long, although the same advice about not holding it for
+/// very long applies. Created using `AtomicSlice::read_arc`.
+#[cfg(target_has_atomic = "ptr")]
+pub struct ArcAtomicSliceReadGuard<T, const BUFS: usize = 2> {
+    atomic_slice: Arc<AtomicSlice<T, BUFS>>,
+    current_slice: u8,
+    version: u64,
+    /// Opts out of the auto traits, which would only require `T: Send`
+    /// through `AtomicSlice`, see the impls of `Send` and `Sync` below
+    _marker: PhantomData<*const T>,
+}
+
+/// A read lock that was taken out of an `AtomicSliceReadGuard` using
+/// `AtomicSliceReadGuard::into_raw`, for carrying it across boundaries that
+/// a Rust guard can't cross, such as C callbacks or generated code. The read
+/// lock stays held until the guard is restored using
+/// `AtomicSliceReadGuard::from_raw` and dropped.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RawReadLock<T> {
+    /// The first element of the locked data
+    pub data: *const T,
+    /// The number of elements of the locked data
+    pub len: usize,
+    /// The version of the locked data
+    pub version: u64,
+    /// The index of the locked partition
+    pub slice: u8,
+}
+
+/// A pair of read lock guards on both the most recently published data of an
+/// `AtomicSlice` and the data that was published right before it, such as
+/// for crossfading from one to the other. Created using
+/// `AtomicSlice::read_with_previous`.
+pub struct AtomicSlicePairReadGuard<'a, T, const BUFS: usize = 2> {
+    current: AtomicSliceReadGuard<'a, T, BUFS>,
+    previous: AtomicSliceReadGuard<'a, T, BUFS>,
+}
+
+/// A future which resolves once a newer version of an `AtomicSlice` has
+/// been published. Created using `AtomicSlice::changed`.
+#[cfg(feature = "async")]
+pub struct Changed<'a, T, const BUFS: usize = 2> {
+    atomic_slice: &'a AtomicSlice<T, BUFS>,
+    since_version: u64,
+}
+
+/// The reason that a call to `AtomicSlice::try_write` did not write
+/// anything.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryWriteError {
+    /// Another thread is currently writing to the `AtomicSlice`
+    WriteInProgress,
+    /// The inactive partition is still being read from by readers who
+    /// acquired it before the most recent write. With more than two
+    /// partitions, this means that all inactive partitions are in use.
+    ReadInProgress,
+}
+
+impl core::fmt::Display for TryWriteError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TryWriteError::WriteInProgress => write!(f, "another write is in progress"),
+            TryWriteError::ReadInProgress => write!(f, "the inactive slice is still being read"),
         }
     }
+}
+
+impl core::error::Error for TryWriteError {}
+
+/// The error returned by `AtomicSlice::write_timeout` when the write could
+/// not begin before the timeout elapsed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WriteTimeout;
+
+impl core::fmt::Display for WriteTimeout {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "timed out waiting to write")
+    }
+}
+
+impl core::error::Error for WriteTimeout {}
+
+/// The error returned by `AtomicSlice::write_checked` when the given slice
+/// doesn't have the same length as the `AtomicSlice`, and by
+/// `AtomicSlice::write_bytes` and `AtomicSlice::write_from_bytes`, which
+/// count both lengths in bytes instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LengthMismatch {
+    /// The length of the `AtomicSlice`
+    pub expected: usize,
+    /// The length of the slice that was passed in
+    pub actual: usize,
+}
+
+impl core::fmt::Display for LengthMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "expected a slice of length {} but got {}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl core::error::Error for LengthMismatch {}
+
+/// The error returned by `AtomicSlice::compare_and_write` when a different
+/// version than the expected one was the most recently published.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VersionMismatch {
+    /// The version that the caller expected
+    pub expected: u64,
+    /// The version that was actually the most recently published
+    pub actual: u64,
+}
+
+impl core::fmt::Display for VersionMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "expected version {} but found version {}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl core::error::Error for VersionMismatch {}
 
+impl<T, const BUFS: usize> AtomicSlice<T, BUFS> {
     /// Get the number of elements
     pub fn len(&self) -> usize {
         self.stride
     }
 
-    /// Acquire a read lock on the slice. Never waits or blocks, and performs
-    /// exactly two atomic operations (in release builds). The returned
-    /// lock guard will be released when it is dropped, performing an additional
-    /// single atomic operation.
-    pub fn read<'a>(&'a self) -> AtomicSliceReadGuard<'a, T> {
-        // Get current slice index while also marking all slices as in use.
-        let status = self
-            .status
-            .fetch_add(constants::INC_ALL_SLICES, Ordering::SeqCst);
+    /// Returns true if the slice has no elements
+    pub fn is_empty(&self) -> bool {
+        self.stride == 0
+    }
 
-        debug_assert!(valid_status(status));
-        debug_assert!(slice_1_use_count(status) < 0xFFFF);
-        debug_assert!(slice_2_use_count(status) < 0xFFFF);
+    /// Get the version of the most recently published data. The version
+    /// starts at zero and increases by one with every write, making it a
+    /// cheap way to find out whether anything has changed since the last time
+    /// the data was looked at. Compare with `AtomicSliceReadGuard::version`.
+    pub fn version(&self) -> u64 {
+        self.protocol.version()
+    }
 
-        let current_slice = (status & constants::CURRENT_SLICE_MASK) as u8;
+    /// Get the index of the partition that new readers are currently pointed
+    /// to. Like `reader_counts` and `is_writing`, this is only a snapshot
+    /// which may be out of date by the time it is returned, and is meant for
+    /// monitoring and debugging rather than for making decisions.
+    pub fn active_slice(&self) -> usize {
+        self.protocol.current_slice() as usize
+    }
 
-        debug_assert!(slice_use_count(current_slice, self.status.load(Ordering::SeqCst)) > 0);
+    /// Get the number of read locks currently held on each partition. This
+    /// briefly includes readers that are in the middle of acquiring a read
+    /// lock and find out that a write was published in the meantime.
+    pub fn reader_counts(&self) -> [usize; BUFS] {
+        self.protocol.reader_counts()
+    }
 
-        // Now that the current slice is known, mark the others as no longer in use
-        let inc_other_slice = if current_slice == 0 {
-            constants::SLICE_2_INC
-        } else {
-            constants::SLICE_1_INC
-        };
-        let status = self.status.fetch_sub(inc_other_slice, Ordering::SeqCst);
-        debug_assert!(valid_status(status));
+    /// Returns true if a writer currently holds exclusive access to the
+    /// write portion, such as while it waits for readers to leave the
+    /// partition it is about to write to.
+    pub fn is_writing(&self) -> bool {
+        self.protocol.is_writing()
+    }
 
-        let stride = self.stride;
-        let offset = current_slice as usize * stride;
-        let slice: &[T] = unsafe {
-            let ptr_box = self.data.get();
-            let ptr_data = (*ptr_box).as_ptr();
-            let ptr_begin = ptr_data.add(offset);
-            std::slice::from_raw_parts(ptr_begin, stride)
+    /// Acquire a read lock on the slice. Never waits or blocks. Usually, this
+    /// performs an atomic load followed by a single atomic read-modify-write
+    /// operation, and if a write is published at just the wrong moment, three
+    /// more (in release builds). The returned lock guard will be released when
+    /// it is dropped, performing an additional single atomic operation.
+    ///
+    /// If the maximum number of simultaneous reads (see `AtomicSlice`) is
+    /// already being held, this method panics instead of letting the count
+    /// overflow. The slice remains fully usable by all other readers and
+    /// writers, and reading succeeds again once some guards are dropped.
+    pub fn read<'a>(&'a self) -> AtomicSliceReadGuard<'a, T, BUFS> {
+        let current_slice = self.protocol.acquire();
+        let slice: &[T] = unsafe { self.slice(current_slice) };
+        let version = unsafe { self.protocol.slice_version(current_slice) };
+
+        AtomicSliceReadGuard {
+            slice,
+            current_slice,
+            status: self.protocol.status(),
+            version,
+        }
+    }
+
+    /// Acquire a read lock on the slice, pass the data to the given function
+    /// and release the lock as soon as it returns, like `read`. Since nothing
+    /// borrowed from the data can outlive the function, the read lock can't
+    /// accidentally be held for longer than intended.
+    ///
+    /// ```
+    /// use atomicslice::AtomicSlice;
+    ///
+    /// let atomic_slice = AtomicSlice::new(vec![1.0_f32, 2.0, 3.0]);
+    /// let peak = atomic_slice.read_with(|data| data.iter().copied().fold(0.0, f32::max));
+    /// assert_eq!(peak, 3.0);
+    /// ```
+    pub fn read_with<R, F: FnOnce(&[T]) -> R>(&self, f: F) -> R {
+        let guard = self.read();
+        f(&guard)
+    }
+
+    /// Choose how writers wait for competing writers and for lingering readers
+    /// of the partition they are about to write to. By default, writers
+    /// spin and, with `std`, eventually yield to the scheduler, see
+    /// `Backoff`.
+    pub fn set_backoff(&mut self, backoff: Backoff) {
+        self.protocol.set_backoff(backoff);
+    }
+
+    /// Returns true if a thread panicked while writing, for example because
+    /// cloning one of the elements panicked. Readers never see the partially
+    /// written data and writing carries on working as usual, but one of the
+    /// inactive partitions may be left holding it, which matters to functions
+    /// passed to `write_with`. `read_with_previous` and `read_back` never
+    /// return it. This stays true until `clear_poison` is called.
+    #[cfg(feature = "std")]
+    pub fn is_poisoned(&self) -> bool {
+        self.protocol.is_poisoned()
+    }
+
+    /// Clear the poisoned state after a panic while writing. See
+    /// `is_poisoned`.
+    #[cfg(feature = "std")]
+    pub fn clear_poison(&self) {
+        self.protocol.clear_poison();
+    }
+
+    /// Get statistics about how often and for how long writers had to wait,
+    /// both for each other and for readers to release the partition about to
+    /// be written to, since the `AtomicSlice` was created or the statistics
+    /// were last reset. This tells whether long-held read lock guards
+    /// actually delay writers.
+    #[cfg(feature = "metrics")]
+    pub fn write_stats(&self) -> WriteStats {
+        self.protocol.write_stats()
+    }
+
+    /// Reset the statistics returned by `write_stats`
+    #[cfg(feature = "metrics")]
+    pub fn reset_write_stats(&self) {
+        self.protocol.reset_write_stats();
+    }
+
+    /// Attempt to acquire a read lock on the slice, like `read`, but return
+    /// `None` instead of panicking if the maximum number of simultaneous
+    /// reads is already being held. This allows callers that hold onto many
+    /// guards at once to back off gracefully.
+    pub fn try_read<'a>(&'a self) -> Option<AtomicSliceReadGuard<'a, T, BUFS>> {
+        let current_slice = self.protocol.try_acquire()?;
+        let slice: &[T] = unsafe { self.slice(current_slice) };
+        let version = unsafe { self.protocol.slice_version(current_slice) };
+
+        Some(AtomicSliceReadGuard {
+            slice,
+            current_slice,
+            status: self.protocol.status(),
+            version,
+        })
+    }
+
+    /// Acquire read locks on both the most recently published data and the
+    /// data that was published right before it, which would otherwise be
+    /// inaccessible. If the previous data is gone because a write to its
+    /// partition panicked, the most recent data before it that is still
+    /// around is returned instead. If there is none, such as before anything
+    /// has been written, both hold the current data.
+    ///
+    /// Since the previous data is normally where the next write goes, this
+    /// briefly waits for any ongoing write to finish, like a writer would.
+    /// For as long as the returned guard is held, writers have one partition
+    /// less to choose from, so with only two partitions, they must wait for
+    /// the guard to be dropped.
+    pub fn read_with_previous(&self) -> AtomicSlicePairReadGuard<'_, T, BUFS> {
+        let (current_slice, previous_slice) = self.protocol.acquire_with_previous();
+        let guard = |slice| AtomicSliceReadGuard {
+            slice: unsafe { self.slice(slice) },
+            current_slice: slice,
+            status: self.protocol.status(),
+            version: unsafe { self.protocol.slice_version(slice) },
         };
 
-        debug_assert!(slice_use_count(current_slice, self.status.load(Ordering::SeqCst)) > 0);
+        AtomicSlicePairReadGuard {
+            current: guard(current_slice),
+            previous: guard(previous_slice),
+        }
+    }
+
+    /// Acquire a read lock on the data that was published `n` versions ago,
+    /// if it is still around. Writers always overwrite the partition holding
+    /// the oldest data that isn't being read from, so the partitions other
+    /// than the current one form a history of the most recent versions, and
+    /// up to `BUFS - 1` versions back can be read this way. Older versions,
+    /// and those overwritten out of order because of lingering readers,
+    /// yield `None`. Reading 0 versions back reads the current data.
+    ///
+    /// Like `read_with_previous`, this briefly waits for any ongoing write to
+    /// finish, and writers have one partition less to choose from while the
+    /// returned guard is held.
+    pub fn read_back(&self, n: u64) -> Option<AtomicSliceReadGuard<'_, T, BUFS>> {
+        let current_slice = self.protocol.acquire_back(n)?;
+        let slice = unsafe { self.slice(current_slice) };
+        let version = unsafe { self.protocol.slice_version(current_slice) };
 
-        AtomicSliceReadGuard {
+        Some(AtomicSliceReadGuard {
             slice,
-            current_slice: current_slice,
-            status: &self.status,
+            current_slice,
+            status: self.protocol.status(),
+            version,
+        })
+    }
+
+    /// Block the current thread until a version newer than `since_version`
+    /// has been published, and return that newer version. Returns immediately
+    /// if this has already happened. The thread is put to sleep while waiting
+    /// rather than spinning, which traps on the main thread of a browser.
+    #[cfg(feature = "std")]
+    pub fn wait_for_update(&self, since_version: u64) -> u64 {
+        self.protocol.wait_for_update(since_version)
+    }
+
+    /// Returns a future which resolves once a version newer than the current
+    /// one has been published, producing that newer version. This is the
+    /// asynchronous counterpart to `wait_for_update` and works with any
+    /// executor.
+    #[cfg(feature = "async")]
+    pub fn changed(&self) -> Changed<'_, T, BUFS> {
+        Changed {
+            atomic_slice: self,
+            since_version: self.version(),
+        }
+    }
+
+    /// Acquire a read lock on the slice only if its version differs from
+    /// `last_seen`, in which case `last_seen` is updated to the version of the
+    /// returned guard. Since versions only ever increase, this means that a
+    /// newer version has been published, as long as `last_seen` came from
+    /// this `AtomicSlice`. If nothing has changed, this returns `None` after a
+    /// single atomic load and without locking anything. Like with
+    /// `copy_if_changed`, starting with a `last_seen` of `u64::MAX` always
+    /// reads the first time.
+    pub fn read_if_newer<'a>(
+        &'a self,
+        last_seen: &mut u64,
+    ) -> Option<AtomicSliceReadGuard<'a, T, BUFS>> {
+        if self.version() == *last_seen {
+            return None;
+        }
+        let guard = self.read();
+        // A newer version may have been published in the meantime, which
+        // can only match if `last_seen` came from somewhere else
+        if guard.version() == *last_seen {
+            return None;
+        }
+        *last_seen = guard.version();
+        Some(guard)
+    }
+
+    /// Acquire a read lock on the slice which can be moved around freely and
+    /// outlive the current scope, such as by sending it to another thread.
+    /// Behaves exactly like `read` except that the returned lock guard keeps
+    /// its own reference to the `AtomicSlice`.
+    #[cfg(target_has_atomic = "ptr")]
+    pub fn read_arc(self: &Arc<Self>) -> ArcAtomicSliceReadGuard<T, BUFS> {
+        let current_slice = self.protocol.acquire();
+        let version = unsafe { self.protocol.slice_version(current_slice) };
+
+        ArcAtomicSliceReadGuard {
+            atomic_slice: Arc::clone(self),
+            current_slice,
+            version,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Write new data in place. The given function receives mutable access
+    /// to the inactive partition, and whatever it leaves there is published
+    /// to readers once it returns. No intermediate buffer is needed.
+    ///
+    /// Note that the inactive partition still holds stale data from an earlier
+    /// write (or from construction), so the function should overwrite every
+    /// element it cares about rather than assume any particular contents.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time. If the current
+    /// thread is itself holding the lock guards that it would have to wait
+    /// for, it would wait forever, and so debug builds panic instead. For
+    /// this purpose, a lock guard that was handed to another thread keeps
+    /// counting towards the thread that created it until it is dropped.
+    /// `write_timeout` doesn't check this, and can wait for such guards.
+    pub fn write_with<F: FnOnce(&mut [T])>(&self, f: F) {
+        let _lock = self.protocol.lock_writer();
+
+        unsafe { self.write_exclusive(f) };
+    }
+
+    /// Write new data in place based on the most recently published data.
+    /// Like `write_with`, except that the given function also receives the
+    /// published data to read from. Since other writers are locked out until
+    /// the new data is published, no concurrent write can be lost in between,
+    /// unlike when calling `read` and then `write`.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn update<F: FnOnce(&[T], &mut [T])>(&self, f: F) {
+        let _lock = self.protocol.lock_writer();
+
+        let previous_i = self.protocol.current_slice();
+        unsafe {
+            // The previous slice may still be read from, but nobody
+            // else can write to it while the write portion is held
+            let previous = self.slice(previous_i);
+            self.write_exclusive(|slice| f(previous, slice));
         }
     }
 
+    /// Write a vector of new data, moving its elements into the `AtomicSlice`
+    /// instead of cloning them. The given vector must have the same length as
+    /// the `AtomicSlice` itself, otherwise this method panics.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn write_vec(&self, data: Vec<T>) {
+        self.write_iter(data.into_iter());
+    }
+
+    /// Write new data produced by an iterator, moving each item straight into
+    /// the `AtomicSlice` without collecting them anywhere first. The iterator
+    /// must produce exactly as many items as the `AtomicSlice` has elements,
+    /// otherwise this method panics without publishing anything.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn write_iter<I: ExactSizeIterator<Item = T>>(&self, mut iter: I) {
+        if iter.len() != self.stride {
+            panic!("Attempted to write slice of the wrong length to AtomicSlice");
+        }
+
+        self.write_with(|slice| {
+            for v in slice.iter_mut() {
+                // Panicking here leaves the data unpublished
+                *v = iter
+                    .next()
+                    .expect("Attempted to write slice of the wrong length to AtomicSlice");
+            }
+        });
+    }
+
+    /// Repeatedly compute new data from the most recently published data until
+    /// it can be published without anyone else having written in between,
+    /// like `AtomicU64::fetch_update`. The given function may thus be called
+    /// several times. If it returns `None`, nothing is written and the version
+    /// of the data it was given is returned as an error. Otherwise, the version
+    /// of the newly published data is returned. The returned vector must have
+    /// the same length as the `AtomicSlice` itself, otherwise this method
+    /// panics.
+    ///
+    /// Unlike `update`, other writers are not locked out while the function is
+    /// running, which suits computations that take a while.
+    pub fn fetch_update<F: FnMut(&[T]) -> Option<Vec<T>>>(&self, mut f: F) -> Result<u64, u64> {
+        loop {
+            // The read lock must be released again before writing, since it
+            // might be holding onto the only partition that can be written to
+            let (version, data) = {
+                let guard = self.read();
+                match f(&guard) {
+                    Some(data) => (guard.version(), data),
+                    None => return Err(guard.version()),
+                }
+            };
+
+            if data.len() != self.stride {
+                panic!("Attempted to write slice of the wrong length to AtomicSlice");
+            }
+
+            let _lock = self.protocol.lock_writer();
+            if self.protocol.version() != version {
+                continue;
+            }
+
+            unsafe {
+                self.write_exclusive(|slice| {
+                    for (v, new_v) in slice.iter_mut().zip(data) {
+                        *v = new_v;
+                    }
+                });
+            }
+            return Ok(version + 1);
+        }
+    }
+
+    /// Split the `AtomicSlice` into a cloneable `Reader` and a single
+    /// `Writer`. Since the `Writer` is known to be the only one, it skips
+    /// the synchronization between competing writers entirely.
+    #[cfg(target_has_atomic = "ptr")]
+    pub fn split(self) -> (Reader<T, BUFS>, Writer<T, BUFS>) {
+        split::split(self)
+    }
+
+    /// Get mutable access to the most recently published data directly, which
+    /// needs no synchronization at all since the `AtomicSlice` can't be shared
+    /// with anyone else at the same time. Changes made this way are seen by
+    /// subsequent readers without a new version being published.
+    pub fn get_mut(&mut self) -> &mut [T] {
+        #[cfg(target_has_atomic = "ptr")]
+        self.snapshot.clear();
+        let offset = self.protocol.current_slice() as usize * self.pitch;
+        &mut self.data.as_mut_slice()[offset..offset + self.stride]
+    }
+
+    /// Consume the `AtomicSlice` and return the most recently published data,
+    /// reusing the allocation that held all partitions. The data of the other
+    /// partitions is dropped, but the returned vector keeps the capacity for
+    /// all of them, which `Vec::shrink_to_fit` can give back. If the
+    /// `AtomicSlice` was created using a custom allocator, the data is moved
+    /// into a new vector from the global allocator instead.
+    pub fn into_vec(mut self) -> Vec<T> {
+        // No reader or writer can be left, so the active slice is final
+        let offset = self.protocol.current_slice() as usize * self.pitch;
+        #[cfg(all(feature = "mlock", unix))]
+        self.unlock_memory();
+        let mut data = core::mem::take(&mut self.data).into_vec();
+        data.drain(..offset);
+        data.truncate(self.stride);
+        data
+    }
+
+    /// Create a new `AtomicSlice` over a buffer that the caller already owns,
+    /// without allocating any memory of its own. The buffer holds all `BUFS`
+    /// partitions one after the other, so its length must be a multiple of
+    /// `BUFS`, otherwise this method panics. The first partition holds the
+    /// initial data, and the others may hold anything, since readers never
+    /// see them before they are written to.
+    pub fn from_boxed_buffer(buffer: Box<[T]>) -> AtomicSlice<T, BUFS> {
+        let mut atomic_slice = Self::from_storage(Storage::new(buffer));
+        atomic_slice.protocol.invalidate_inactive();
+        atomic_slice
+    }
+
+    /// Create a new `AtomicSlice` over a buffer that is borrowed for the rest
+    /// of the program, such as a `static` placed by the linker, without any
+    /// heap allocation. Otherwise identical to `from_boxed_buffer`.
+    ///
+    /// ```
+    /// use atomicslice::AtomicSlice;
+    ///
+    /// let buffer: &'static mut [u32] = Box::leak(Box::new([1, 2, 3, 0, 0, 0]));
+    /// let atomic_slice: AtomicSlice<u32> = AtomicSlice::new_in_buffer(buffer);
+    /// assert_eq!(*atomic_slice.read(), [1, 2, 3]);
+    /// ```
+    ///
+    /// Since statics are never dropped, the elements left in the buffer are
+    /// never dropped either when the `AtomicSlice` is dropped.
+    pub fn new_in_buffer(buffer: &'static mut [T]) -> AtomicSlice<T, BUFS> {
+        let mut atomic_slice = Self::from_storage(Storage::new_static(buffer));
+        atomic_slice.protocol.invalidate_inactive();
+        atomic_slice
+    }
+
+    pub(crate) fn from_storage(data: Storage<T>) -> AtomicSlice<T, BUFS> {
+        if !data.len().is_multiple_of(BUFS) {
+            panic!("Attempted to create AtomicSlice over buffer whose length isn't a multiple of the number of partitions");
+        }
+        let stride = data.len() / BUFS;
+        AtomicSlice::from_partitions(data, stride, stride)
+    }
+
+    /// Create an `AtomicSlice` whose partitions of `stride` elements each
+    /// start `pitch` elements apart in the given storage
+    pub(crate) fn from_partitions(
+        data: Storage<T>,
+        stride: usize,
+        pitch: usize,
+    ) -> AtomicSlice<T, BUFS> {
+        let protocol = Protocol::new();
+
+        debug_assert!(stride <= pitch && pitch * BUFS == data.len());
+        AtomicSlice {
+            data,
+            stride,
+            pitch,
+            protocol,
+            #[cfg(all(feature = "mlock", unix))]
+            locked: false,
+            #[cfg(target_has_atomic = "ptr")]
+            snapshot: SnapshotCache::new(),
+        }
+    }
+}
+
+impl<T: Clone> AtomicSlice<T> {
+    /// Create a new `AtomicSlice` from a vector of data. The `AtomicSlice`
+    /// will have the length of this vector for its entire lifetime.
+    pub fn new(data: Vec<T>) -> AtomicSlice<T> {
+        AtomicSlice::new_with_buffers(data)
+    }
+}
+
+impl<T: Clone, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Create a new `AtomicSlice` with `BUFS` partitions from a vector of
+    /// data, for example using `AtomicSlice::<f32, 3>::new_with_buffers(data)`.
+    /// Otherwise identical to `new`.
+    pub fn new_with_buffers(mut data: Vec<T>) -> AtomicSlice<T, BUFS> {
+        let protocol = Protocol::new();
+
+        let stride = data.len();
+        // Fill the other partitions with copies of the initial data
+        data.reserve_exact(stride * (BUFS - 1));
+        for _ in 1..BUFS {
+            data.extend_from_within(..stride);
+        }
+        AtomicSlice {
+            data: Storage::new(data.into_boxed_slice()),
+            stride,
+            pitch: stride,
+            protocol,
+            #[cfg(all(feature = "mlock", unix))]
+            locked: false,
+            #[cfg(target_has_atomic = "ptr")]
+            snapshot: SnapshotCache::new(),
+        }
+    }
+
+    /// Create a new `AtomicSlice` of the given length whose elements are
+    /// produced by calling `f` with each index in turn. The elements are
+    /// written straight into the allocation that holds all partitions,
+    /// without going through an intermediate vector.
+    ///
+    /// ```
+    /// use atomicslice::AtomicSlice;
+    ///
+    /// let squares: AtomicSlice<usize> = AtomicSlice::from_fn(4, |i| i * i);
+    /// assert_eq!(*squares.read(), [0, 1, 4, 9]);
+    /// ```
+    pub fn from_fn<F: FnMut(usize) -> T>(len: usize, f: F) -> AtomicSlice<T, BUFS> {
+        let total = len
+            .checked_mul(BUFS)
+            .expect("Attempted to create AtomicSlice whose partitions don't fit into memory");
+        // Allocate room for all partitions up front so that nothing is moved
+        let mut data = Vec::with_capacity(total);
+        data.extend((0..len).map(f));
+        AtomicSlice::new_with_buffers(data)
+    }
+
+    /// Create a new `AtomicSlice` of the given length whose elements, in
+    /// every partition, are clones of `value`, like `vec![value; len]`.
+    pub fn from_elem(value: T, len: usize) -> AtomicSlice<T, BUFS> {
+        let total = len
+            .checked_mul(BUFS)
+            .expect("Attempted to create AtomicSlice whose partitions don't fit into memory");
+        let mut data = Vec::with_capacity(total);
+        data.resize(total, value);
+        AtomicSlice::from_storage(Storage::new(data.into_boxed_slice()))
+    }
+
+    /// Copy the current contents of the slice into the given slice, which
+    /// must have the same length as the `AtomicSlice` itself, otherwise this
+    /// method panics. The read lock is held only for the duration of the
+    /// copy, making this a convenient way to avoid delaying writers.
+    pub fn read_into(&self, out: &mut [T]) {
+        if out.len() != self.stride {
+            panic!("Attempted to read into slice of the wrong length from AtomicSlice");
+        }
+
+        let guard = self.read();
+        out.clone_from_slice(&guard);
+    }
+
+    /// Copy the current contents of the slice into a new vector. Like
+    /// `read_into`, the read lock is only held for the duration of the copy.
+    pub fn to_vec(&self) -> Vec<T> {
+        let guard = self.read();
+        guard.to_vec()
+    }
+
+    /// Copy the current contents of the slice into the given vector, but only
+    /// if their version differs from `last_version`, which is then updated to
+    /// the version that was copied. This follows the same rule as
+    /// `read_if_newer`. Returns whether anything was copied. Like
+    /// `read_into`, the read lock is only held for the duration of the copy,
+    /// and no read lock is acquired at all if nothing changed.
+    ///
+    /// This keeps a local copy up to date, for example in a GUI which redraws
+    /// only when the data changed. Starting with a `last_version` of
+    /// `u64::MAX`, the first call always copies.
+    ///
+    /// ```
+    /// use atomicslice::AtomicSlice;
+    ///
+    /// let atomic_slice = AtomicSlice::new(vec![1, 2, 3]);
+    /// let (mut local, mut last_version) = (Vec::new(), u64::MAX);
+    /// assert!(atomic_slice.copy_if_changed(&mut local, &mut last_version));
+    /// assert!(!atomic_slice.copy_if_changed(&mut local, &mut last_version));
+    /// assert_eq!(local, [1, 2, 3]);
+    /// ```
+    pub fn copy_if_changed(&self, out: &mut Vec<T>, last_version: &mut u64) -> bool {
+        if self.version() == *last_version {
+            return false;
+        }
+        let guard = self.read();
+        // A newer version may have been published in the meantime, which
+        // can only match if `last_version` came from somewhere else
+        if guard.version() == *last_version {
+            return false;
+        }
+        out.clear();
+        out.extend_from_slice(&guard);
+        *last_version = guard.version();
+        true
+    }
+
+    /// Clone a single element of the current contents, or return `None` if
+    /// the index is out of bounds. Like `read_into`, the read lock is only
+    /// held for the duration of the clone.
+    pub fn get(&self, index: usize) -> Option<T> {
+        let guard = self.read();
+        guard.get(index).cloned()
+    }
+
     /// Write a slice of new data. The given slice must have the same length as
     /// the `AtomicSlice` itself, otherwise this method panics.
     ///
     /// This method may block if other threads are writing and if any readers
     /// are holding lock guards for extended periods of time.
+    ///
+    /// For element types that are `Copy`, the data is copied in one go as if
+    /// by `memcpy` rather than being cloned one element at a time.
     pub fn write(&self, data: &[T]) {
-        let stride = self.stride;
-        if data.len() != stride {
+        if let Err(error) = self.write_checked(data) {
+            panic!("Attempted to write slice of the wrong length to AtomicSlice ({error})");
+        }
+    }
+
+    /// Write a slice of new data, like `write`, but return an error instead
+    /// of panicking if the given slice doesn't have the same length as the
+    /// `AtomicSlice` itself. Nothing is written in that case.
+    pub fn write_checked(&self, data: &[T]) -> Result<(), LengthMismatch> {
+        if data.len() != self.stride {
+            return Err(LengthMismatch {
+                expected: self.stride,
+                actual: data.len(),
+            });
+        }
+
+        self.write_with(|slice| slice.clone_from_slice(data));
+        Ok(())
+    }
+
+    /// Write copies of the given value to every element, without building
+    /// a temporary buffer.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn fill(&self, value: T) {
+        self.write_with(|slice| slice.fill(value));
+    }
+
+    /// Write a slice of new data only if the most recently published version
+    /// is still `expected_version`, typically the version of a guard that the
+    /// new data was computed from, and return the new version. If anyone else
+    /// has written in the meantime, nothing is written and an error is returned
+    /// instead. The given slice must have the same length as the `AtomicSlice`
+    /// itself, otherwise this method panics.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn compare_and_write(
+        &self,
+        expected_version: u64,
+        data: &[T],
+    ) -> Result<u64, VersionMismatch> {
+        if data.len() != self.stride {
             panic!("Attempted to write slice of the wrong length to AtomicSlice");
         }
 
-        // Wait for exclusive access to the write portion
-        while !self
-            .currently_writing
-            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
-            .is_ok()
-        {
-            std::hint::spin_loop();
+        let _lock = self.protocol.lock_writer();
+
+        // Only writers change the version, and they are locked out now
+        let version = self.protocol.version();
+        if version != expected_version {
+            return Err(VersionMismatch {
+                expected: expected_version,
+                actual: version,
+            });
         }
 
-        // Load the current status
-        let status = self.status.load(Ordering::SeqCst);
-        debug_assert!(valid_status(status));
-        let i = (status & constants::CURRENT_SLICE_MASK) as u8;
-        let next_i = i ^ 1;
+        unsafe { self.write_exclusive(|slice| slice.clone_from_slice(data)) };
+        Ok(version + 1)
+    }
+
+    /// Write new data to only a part of the slice, starting at the given
+    /// offset, while the rest keeps its currently published contents. The
+    /// range must lie within the `AtomicSlice`, otherwise this method panics.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn write_range(&self, offset: usize, data: &[T]) {
+        let end = match offset.checked_add(data.len()) {
+            Some(end) if end <= self.stride => end,
+            _ => panic!("Attempted to write range out of bounds of AtomicSlice"),
+        };
+
+        self.update(|previous, slice| {
+            slice[..offset].clone_from_slice(&previous[..offset]);
+            slice[offset..end].clone_from_slice(data);
+            slice[end..].clone_from_slice(&previous[end..]);
+        });
+    }
+
+    /// Attempt to write a slice of new data without waiting. If another
+    /// thread is currently writing, or if the inactive partition is still
+    /// being read from, nothing is written and an error is returned
+    /// immediately. The given slice must have the same length as the
+    /// `AtomicSlice` itself, otherwise this method panics.
+    pub fn try_write(&self, data: &[T]) -> Result<(), TryWriteError> {
+        if data.len() != self.stride {
+            panic!("Attempted to write slice of the wrong length to AtomicSlice");
+        }
+
+        let Some(_lock) = self.protocol.try_lock_writer() else {
+            return Err(TryWriteError::WriteInProgress);
+        };
+
+        let Some(next_i) = self.protocol.free_slice() else {
+            return Err(TryWriteError::ReadInProgress);
+        };
+
+        unsafe { self.slice_mut(next_i) }.clone_from_slice(data);
+
+        self.protocol.publish(next_i);
+        Ok(())
+    }
+
+    /// Write a slice of new data, giving up if the write could not begin
+    /// within the given amount of time. Writing can be delayed both by
+    /// other threads that are writing and by readers still holding onto the
+    /// inactive partition. If the time runs out, nothing is written and an
+    /// error is returned. A timeout too long for any clock to reach, such as
+    /// `Duration::MAX`, waits for as long as it takes. The given slice must
+    /// have the same length as the `AtomicSlice` itself, otherwise this
+    /// method panics.
+    ///
+    /// Not available on `wasm32-unknown-unknown`, which has no clock.
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
+    pub fn write_timeout(&self, data: &[T], timeout: Duration) -> Result<(), WriteTimeout> {
+        if data.len() != self.stride {
+            panic!("Attempted to write slice of the wrong length to AtomicSlice");
+        }
+
+        let deadline = Instant::now().checked_add(timeout);
+        let timed_out = || deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        let mut waiter = self.protocol.waiter();
+
+        // Wait for exclusive access to the write portion
+        let _lock = loop {
+            if let Some(lock) = self.protocol.try_lock_writer() {
+                break lock;
+            }
+            if timed_out() {
+                return Err(WriteTimeout);
+            }
+            waiter.wait();
+        };
 
         // Wait to ensure the next slice is not being used
-        loop {
-            let status = self.status.load(Ordering::SeqCst);
-            debug_assert!(valid_status(status));
-            if slice_use_count(next_i, status) == 0 {
-                break;
+        let next_i = loop {
+            if let Some(i) = self.protocol.free_slice() {
+                break i;
+            }
+            if timed_out() {
+                return Err(WriteTimeout);
             }
-            std::hint::spin_loop();
+            waiter.wait();
+        };
+
+        unsafe { self.slice_mut(next_i) }.clone_from_slice(data);
+
+        self.protocol.publish(next_i);
+        Ok(())
+    }
+
+    /// Exchange the contents of the `AtomicSlice` with those of the given
+    /// buffer. The buffer's contents are moved in and published, and the
+    /// buffer is then refilled with the values that were published just
+    /// before. This allows a writer to keep reusing a single buffer without
+    /// any allocations. The given buffer must have the same length as the
+    /// `AtomicSlice` itself, otherwise this method panics.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn swap(&self, data: &mut [T]) {
+        if data.len() != self.stride {
+            panic!("Attempted to write slice of the wrong length to AtomicSlice");
         }
 
-        // Copy data to the next slice
-        let offset = (next_i as usize) * stride;
-        let slice: &mut [T] = unsafe {
-            let ptr_box = self.data.get();
-            let ptr_data = (*ptr_box).as_mut_ptr();
-            let ptr_begin = ptr_data.add(offset);
-            std::slice::from_raw_parts_mut(ptr_begin, stride)
-        };
-        for (i, v) in slice.iter_mut().enumerate() {
-            *v = data[i].clone();
+        let _lock = self.protocol.lock_writer();
+
+        let previous_i = self.protocol.current_slice();
+        unsafe {
+            self.write_exclusive(|slice| slice.swap_with_slice(data));
+
+            // The previous slice may still be read from, but nobody
+            // else can write to it while the write portion is held
+            data.clone_from_slice(self.slice(previous_i));
+        }
+    }
+}
+
+impl<T: Clone + PartialEq, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Write a slice of new data only if it differs from the most recently
+    /// published data, and return whether it did. If nothing has changed, the
+    /// version stays the same and nobody waiting for updates is woken up. The
+    /// given slice must have the same length as the `AtomicSlice` itself,
+    /// otherwise this method panics.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn write_if_changed(&self, data: &[T]) -> bool {
+        if data.len() != self.stride {
+            panic!("Attempted to write slice of the wrong length to AtomicSlice");
+        }
+
+        let _lock = self.protocol.lock_writer();
+
+        // Nobody else can write to the current slice while the write
+        // portion is held
+        if unsafe { self.slice(self.protocol.current_slice()) } == data {
+            return false;
         }
 
-        // Point all new readers to the other slice
-        let status = self.status.fetch_xor(1, Ordering::SeqCst);
-        debug_assert!(valid_status(status));
+        unsafe { self.write_exclusive(|slice| slice.clone_from_slice(data)) };
+        true
+    }
+}
+
+impl<T, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Get shared access to one of the slices.
+    ///
+    /// Safety: the slice must not be written to while the returned reference
+    /// is alive, i.e. the caller must either hold a read lock on it or hold
+    /// exclusive access to the write portion.
+    unsafe fn slice(&self, slice: u8) -> &[T] {
+        let stride = self.stride;
+        let offset = (slice as usize) * self.pitch;
+        let ptr_begin = self.data.as_ptr().add(offset);
+        core::slice::from_raw_parts(ptr_begin, stride)
+    }
+
+    /// Get mutable access to one of the slices.
+    ///
+    /// Safety: the caller must hold exclusive access to the write portion
+    /// and the slice must not be in use by any readers.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn slice_mut(&self, slice: u8) -> &mut [T] {
+        let stride = self.stride;
+        let offset = (slice as usize) * self.pitch;
+        let ptr_begin = self.data.as_ptr().add(offset);
+        core::slice::from_raw_parts_mut(ptr_begin, stride)
+    }
+
+    /// Wait for a slice to be out of use, let the given function fill it
+    /// in, and then publish it.
+    ///
+    /// Safety: the caller must hold exclusive access to the write portion,
+    /// either by locking it or by being the only possible writer.
+    unsafe fn write_exclusive<F: FnOnce(&mut [T])>(&self, f: F) {
+        // Wait to ensure the next slice is not being used
+        let next_i = self.protocol.wait_for_free_slice();
+
+        // Let the caller fill in the next slice
+        f(self.slice_mut(next_i));
 
-        // Release exclusive access to the write portion
-        self.currently_writing
-            .compare_exchange(true, false, Ordering::SeqCst, Ordering::SeqCst)
-            .unwrap();
+        self.protocol.publish(next_i);
     }
 }
 
+// Superseded by `codegen::data_ptr` and `codegen::status_ptr`
 #[doc(hidden)]
-impl<T> AtomicSlice<T> {
+impl<T, const BUFS: usize> AtomicSlice<T, BUFS> {
     pub unsafe fn raw_data(&self) -> *const T {
-        let ptr_box = self.data.get();
-        (*ptr_box).as_ptr()
+        self.data.as_ptr()
+    }
+
+    pub unsafe fn raw_status(&self) -> *const AtomicStatus {
+        self.protocol.status()
+    }
+}
+
+unsafe impl<T: Send + Sync, const BUFS: usize> Sync for AtomicSlice<T, BUFS> {}
+unsafe impl<T: Send, const BUFS: usize> Send for AtomicSlice<T, BUFS> {}
+
+// A panic while writing never leaves readers looking at partially written
+// data, see `is_poisoned`
+impl<T: RefUnwindSafe, const BUFS: usize> RefUnwindSafe for AtomicSlice<T, BUFS> {}
+impl<T: UnwindSafe, const BUFS: usize> UnwindSafe for AtomicSlice<T, BUFS> {}
+
+/// Cloning creates a new, independent `AtomicSlice` from a snapshot of the
+/// current contents, which starts out at version 0 like any other new
+/// `AtomicSlice`. The choice of `Backoff` carries over.
+impl<T: Clone, const BUFS: usize> Clone for AtomicSlice<T, BUFS> {
+    fn clone(&self) -> Self {
+        let mut atomic_slice = AtomicSlice::new_with_buffers(self.to_vec());
+        atomic_slice.set_backoff(self.protocol.backoff());
+        atomic_slice
+    }
+}
+
+impl<T: Clone, const BUFS: usize> From<Vec<T>> for AtomicSlice<T, BUFS> {
+    fn from(data: Vec<T>) -> Self {
+        AtomicSlice::new_with_buffers(data)
+    }
+}
+
+impl<T: Clone, const BUFS: usize> From<&[T]> for AtomicSlice<T, BUFS> {
+    fn from(data: &[T]) -> Self {
+        AtomicSlice::new_with_buffers(data.to_vec())
     }
+}
+
+impl<T: Clone, const BUFS: usize, const N: usize> From<[T; N]> for AtomicSlice<T, BUFS> {
+    fn from(data: [T; N]) -> Self {
+        AtomicSlice::new_with_buffers(data.into())
+    }
+}
+
+impl<T: Clone, const BUFS: usize> FromIterator<T> for AtomicSlice<T, BUFS> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        AtomicSlice::new_with_buffers(iter.into_iter().collect())
+    }
+}
 
-    pub unsafe fn raw_status(&self) -> *const AtomicU64 {
-        &self.status
+/// Creates an empty `AtomicSlice`, which stays empty for its entire lifetime
+impl<T, const BUFS: usize> Default for AtomicSlice<T, BUFS> {
+    fn default() -> Self {
+        AtomicSlice {
+            data: Storage::default(),
+            stride: 0,
+            pitch: 0,
+            protocol: Protocol::new(),
+            #[cfg(all(feature = "mlock", unix))]
+            locked: false,
+            #[cfg(target_has_atomic = "ptr")]
+            snapshot: SnapshotCache::new(),
+        }
     }
 }
 
-unsafe impl<T: Send> Sync for AtomicSlice<T> {}
-unsafe impl<T: Send> Send for AtomicSlice<T> {}
+/// Compares the current contents of both `AtomicSlice`s, each read under a
+/// read lock which is released right away. Since either of them may be
+/// written to at any moment, the result only says that the contents were
+/// equal at some point during the call, not that they still are. Versions
+/// are not compared.
+impl<T: PartialEq, const BUFS: usize> PartialEq for AtomicSlice<T, BUFS> {
+    #[allow(clippy::eq_op)]
+    fn eq(&self, other: &Self) -> bool {
+        // Reading twice could observe two different versions, while the
+        // elements still decide whether the contents equal themselves
+        if core::ptr::eq(self, other) {
+            let guard = self.read();
+            return *guard == *guard;
+        }
+        *self.read() == *other.read()
+    }
+}
+
+impl<T: Eq, const BUFS: usize> Eq for AtomicSlice<T, BUFS> {}
 
-impl<'a, T> Deref for AtomicSliceReadGuard<'a, T> {
+/// Hashes the current contents, read under a read lock which is released
+/// right away. Like comparing, this is only meaningful for as long as the
+/// `AtomicSlice` isn't written to, so an `AtomicSlice` which is still being
+/// written to should not be used as a key in a `HashMap` or similar.
+impl<T: core::hash::Hash, const BUFS: usize> core::hash::Hash for AtomicSlice<T, BUFS> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.read().hash(state);
+    }
+}
+
+/// Shows the length, the current version and the current contents, the
+/// latter of which are cut short after `DEBUG_MAX_ELEMENTS` elements. The
+/// contents are read under a read lock which is released right away, and
+/// are left out if there are too many simultaneous reads.
+impl<T: core::fmt::Debug, const BUFS: usize> core::fmt::Debug for AtomicSlice<T, BUFS> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut s = f.debug_struct("AtomicSlice");
+        s.field("len", &self.stride);
+        match self.try_read() {
+            Some(guard) => s
+                .field("version", &guard.version())
+                .field("data", &Truncated(&guard))
+                .finish(),
+            None => s.field("version", &self.version()).finish_non_exhaustive(),
+        }
+    }
+}
+
+/// Lets a read lock guard be passed wherever a slice is accepted generically
+/// and be iterated over by reference, just like the slice it dereferences to
+macro_rules! impl_slice_traits {
+    ($guard:ident $(, $lifetime:lifetime)?) => {
+        impl<$($lifetime,)? T, const BUFS: usize> AsRef<[T]> for $guard<$($lifetime,)? T, BUFS> {
+            fn as_ref(&self) -> &[T] {
+                self
+            }
+        }
+
+        impl<$($lifetime,)? T, const BUFS: usize> core::borrow::Borrow<[T]>
+            for $guard<$($lifetime,)? T, BUFS>
+        {
+            fn borrow(&self) -> &[T] {
+                self
+            }
+        }
+
+        impl<'r, $($lifetime,)? T, const BUFS: usize> IntoIterator
+            for &'r $guard<$($lifetime,)? T, BUFS>
+        {
+            type Item = &'r T;
+            type IntoIter = core::slice::Iter<'r, T>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+    };
+}
+
+impl_slice_traits!(AtomicSliceReadGuard, 'a);
+impl_slice_traits!(MappedAtomicSliceReadGuard, 'a);
+#[cfg(target_has_atomic = "ptr")]
+impl_slice_traits!(ArcAtomicSliceReadGuard);
+
+impl<'a, T, const BUFS: usize> Deref for AtomicSliceReadGuard<'a, T, BUFS> {
     type Target = [T];
 
     fn deref(&self) -> &Self::Target {
@@ -250,15 +1534,310 @@ impl<'a, T> Deref for AtomicSliceReadGuard<'a, T> {
     }
 }
 
-impl<'a, T> Drop for AtomicSliceReadGuard<'a, T> {
+/// Cloning a guard acquires another read lock on the very same data,
+/// without any chance of observing a different version of it. Like
+/// `AtomicSlice::read`, this panics if there are too many simultaneous reads.
+impl<'a, T, const BUFS: usize> Clone for AtomicSliceReadGuard<'a, T, BUFS> {
+    fn clone(&self) -> Self {
+        retain::<BUFS>(self.status, self.current_slice);
+        AtomicSliceReadGuard {
+            slice: self.slice,
+            current_slice: self.current_slice,
+            version: self.version,
+            status: self.status,
+        }
+    }
+}
+
+impl<'a, T, const BUFS: usize> Drop for AtomicSliceReadGuard<'a, T, BUFS> {
     fn drop(&mut self) {
-        let inc_slice = if self.current_slice == 0 {
-            constants::SLICE_1_INC
-        } else {
-            constants::SLICE_2_INC
+        release::<BUFS>(self.status, self.current_slice);
+    }
+}
+
+/// Shows the version and the locked data, the latter of which is cut short
+/// after `DEBUG_MAX_ELEMENTS` elements. Dereference the guard to show all
+/// of it.
+impl<'a, T: core::fmt::Debug, const BUFS: usize> core::fmt::Debug
+    for AtomicSliceReadGuard<'a, T, BUFS>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AtomicSliceReadGuard")
+            .field("version", &self.version)
+            .field("data", &Truncated(self.slice))
+            .finish()
+    }
+}
+
+impl<'a, T, const BUFS: usize> AtomicSliceReadGuard<'a, T, BUFS> {
+    /// The version of the data that this guard is holding onto.
+    /// See `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Narrow the guard down to a view of some part of the data, such as a
+    /// sub-range or a single header element, while keeping the read lock
+    /// held. This is analogous to `RwLockReadGuard::map`.
+    pub fn map<U, F>(self, f: F) -> MappedAtomicSliceReadGuard<'a, U, BUFS>
+    where
+        F: FnOnce(&[T]) -> &[U],
+    {
+        // If f panics, self is dropped and the read lock is released
+        let slice = f(self.slice);
+        let guard = ManuallyDrop::new(self);
+        MappedAtomicSliceReadGuard {
+            slice,
+            current_slice: guard.current_slice,
+            status: guard.status,
+            version: guard.version,
+        }
+    }
+
+    /// Give up the guard without releasing its read lock, and return what
+    /// is needed to read the data and to restore the guard later on using
+    /// `from_raw`. The read lock is leaked unless that happens, which keeps
+    /// writers from ever writing to the locked partition again.
+    pub fn into_raw(self) -> RawReadLock<T> {
+        let guard = ManuallyDrop::new(self);
+        RawReadLock {
+            data: guard.slice.as_ptr(),
+            len: guard.slice.len(),
+            version: guard.version,
+            slice: guard.current_slice,
+        }
+    }
+
+    /// Restore a guard from a read lock that was given up using `into_raw`,
+    /// which then releases the read lock when dropped as usual.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must have been returned by `into_raw` of a guard of the given
+    /// `AtomicSlice`, and must be restored at most once. Copies of `raw`
+    /// must not be used anymore once the restored guard is dropped.
+    pub unsafe fn from_raw(
+        atomic_slice: &'a AtomicSlice<T, BUFS>,
+        raw: RawReadLock<T>,
+    ) -> AtomicSliceReadGuard<'a, T, BUFS> {
+        paranoid_assert!(
+            (raw.slice as usize) < BUFS && raw.len == atomic_slice.len(),
+            "RawReadLock of partition {} with {} elements doesn't belong to this AtomicSlice",
+            raw.slice,
+            raw.len
+        );
+        AtomicSliceReadGuard {
+            slice: core::slice::from_raw_parts(raw.data, raw.len),
+            current_slice: raw.slice,
+            status: atomic_slice.protocol.status(),
+            version: raw.version,
+        }
+    }
+
+    /// Narrow the guard down to a sub-range of the data, while keeping the
+    /// read lock held. Panics if the range is out of bounds, like indexing.
+    pub fn slice<R: RangeBounds<usize>>(self, range: R) -> MappedAtomicSliceReadGuard<'a, T, BUFS> {
+        self.map(|s| s).slice(range)
+    }
+
+    /// Split the guard into two guards of the data before and after the
+    /// given index, which both keep the read lock held on the very same
+    /// data, so that disjoint parts of it can be handed to different places.
+    /// Panics if `mid > len()`, or like `AtomicSlice::read` if there are too
+    /// many simultaneous reads.
+    pub fn split_at(
+        self,
+        mid: usize,
+    ) -> (
+        MappedAtomicSliceReadGuard<'a, T, BUFS>,
+        MappedAtomicSliceReadGuard<'a, T, BUFS>,
+    ) {
+        self.map(|s| s).split_at(mid)
+    }
+}
+
+impl<'a, T, const BUFS: usize> AtomicSlicePairReadGuard<'a, T, BUFS> {
+    /// The most recently published data at the time of locking, together
+    /// with its version
+    pub fn current(&self) -> &AtomicSliceReadGuard<'a, T, BUFS> {
+        &self.current
+    }
+
+    /// The data that was published right before the current data, together
+    /// with its version
+    pub fn previous(&self) -> &AtomicSliceReadGuard<'a, T, BUFS> {
+        &self.previous
+    }
+
+    /// Split the pair into the guards of the current and the previous data
+    pub fn into_parts(
+        self,
+    ) -> (
+        AtomicSliceReadGuard<'a, T, BUFS>,
+        AtomicSliceReadGuard<'a, T, BUFS>,
+    ) {
+        (self.current, self.previous)
+    }
+}
+
+impl<'a, T, const BUFS: usize> Deref for MappedAtomicSliceReadGuard<'a, T, BUFS> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.slice
+    }
+}
+
+impl<'a, T, const BUFS: usize> Clone for MappedAtomicSliceReadGuard<'a, T, BUFS> {
+    fn clone(&self) -> Self {
+        retain::<BUFS>(self.status, self.current_slice);
+        MappedAtomicSliceReadGuard {
+            slice: self.slice,
+            current_slice: self.current_slice,
+            version: self.version,
+            status: self.status,
+        }
+    }
+}
+
+impl<'a, T, const BUFS: usize> Drop for MappedAtomicSliceReadGuard<'a, T, BUFS> {
+    fn drop(&mut self) {
+        release::<BUFS>(self.status, self.current_slice);
+    }
+}
+
+impl<'a, T, const BUFS: usize> MappedAtomicSliceReadGuard<'a, T, BUFS> {
+    /// The version of the data that this guard is holding onto.
+    /// See `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Narrow the guard down even further. See `AtomicSliceReadGuard::map`.
+    pub fn map<U, F>(self, f: F) -> MappedAtomicSliceReadGuard<'a, U, BUFS>
+    where
+        F: FnOnce(&[T]) -> &[U],
+    {
+        // If f panics, self is dropped and the read lock is released
+        let slice = f(self.slice);
+        let guard = ManuallyDrop::new(self);
+        MappedAtomicSliceReadGuard {
+            slice,
+            current_slice: guard.current_slice,
+            status: guard.status,
+            version: guard.version,
+        }
+    }
+
+    /// Narrow the guard down to a sub-range. See `AtomicSliceReadGuard::slice`.
+    pub fn slice<R: RangeBounds<usize>>(self, range: R) -> MappedAtomicSliceReadGuard<'a, T, BUFS> {
+        let range = (range.start_bound().cloned(), range.end_bound().cloned());
+        self.map(|s| &s[range])
+    }
+
+    /// Split the guard in two. See `AtomicSliceReadGuard::split_at`.
+    pub fn split_at(
+        self,
+        mid: usize,
+    ) -> (
+        MappedAtomicSliceReadGuard<'a, T, BUFS>,
+        MappedAtomicSliceReadGuard<'a, T, BUFS>,
+    ) {
+        let (front, back) = self.slice.split_at(mid);
+        // The read lock that is already held goes to the front, and another
+        // one is acquired for the back
+        retain::<BUFS>(self.status, self.current_slice);
+        let guard = ManuallyDrop::new(self);
+        let split = |slice| MappedAtomicSliceReadGuard {
+            slice,
+            current_slice: guard.current_slice,
+            status: guard.status,
+            version: guard.version,
         };
-        let status = self.status.fetch_sub(inc_slice, Ordering::SeqCst);
-        debug_assert!(valid_status(status));
-        debug_assert!(slice_use_count(self.current_slice, status) > 0);
+        (split(front), split(back))
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T, const BUFS: usize> Deref for ArcAtomicSliceReadGuard<T, BUFS> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.atomic_slice.slice(self.current_slice) }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a, T, const BUFS: usize> Future for Changed<'a, T, BUFS> {
+    type Output = u64;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u64> {
+        let mut version = 0;
+        let atomic_slice = self.atomic_slice;
+        let since_version = self.since_version;
+        atomic_slice
+            .protocol
+            .notifier
+            .poll_until(cx, || {
+                version = atomic_slice.version();
+                version > since_version
+            })
+            .map(|()| version)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T, const BUFS: usize> ArcAtomicSliceReadGuard<T, BUFS> {
+    /// The version of the data that this guard is holding onto.
+    /// See `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T, const BUFS: usize> Clone for ArcAtomicSliceReadGuard<T, BUFS> {
+    fn clone(&self) -> Self {
+        retain::<BUFS>(self.atomic_slice.protocol.status(), self.current_slice);
+        ArcAtomicSliceReadGuard {
+            atomic_slice: Arc::clone(&self.atomic_slice),
+            current_slice: self.current_slice,
+            version: self.version,
+            _marker: PhantomData,
+        }
+    }
+}
+
+// Threads holding guards on the same data share references to its elements,
+// and whichever thread drops the last guard may drop the elements too
+#[cfg(target_has_atomic = "ptr")]
+unsafe impl<T: Send + Sync, const BUFS: usize> Send for ArcAtomicSliceReadGuard<T, BUFS> {}
+#[cfg(target_has_atomic = "ptr")]
+unsafe impl<T: Send + Sync, const BUFS: usize> Sync for ArcAtomicSliceReadGuard<T, BUFS> {}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T, const BUFS: usize> Drop for ArcAtomicSliceReadGuard<T, BUFS> {
+    fn drop(&mut self) {
+        release::<BUFS>(self.atomic_slice.protocol.status(), self.current_slice);
+    }
+}
+
+/// The number of elements beyond which the `Debug` implementations of
+/// `AtomicSlice` and its read guard cut the data short
+const DEBUG_MAX_ELEMENTS: usize = 32;
+
+/// Formats a slice like `Debug` does, except that only the first
+/// `DEBUG_MAX_ELEMENTS` elements are shown
+struct Truncated<'a, T>(&'a [T]);
+
+impl<'a, T: core::fmt::Debug> core::fmt::Debug for Truncated<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut list = f.debug_list();
+        list.entries(self.0.iter().take(DEBUG_MAX_ELEMENTS));
+        if self.0.len() > DEBUG_MAX_ELEMENTS {
+            list.finish_non_exhaustive()
+        } else {
+            list.finish()
+        }
     }
 }
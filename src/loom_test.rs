@@ -0,0 +1,204 @@
+//! Model-checked tests of the read and write protocol. Run these using
+//! `RUSTFLAGS="--cfg loom" cargo test --release --lib`, or equivalently
+//! `cargo test --release --lib --features loom`.
+
+use loom::{cell::UnsafeCell, sync::Arc, thread};
+
+use crate::{AtomicSlice, Backoff, ShardedAtomicSlice};
+
+/// An element whose accesses are tracked by loom, which fails the test if
+/// a write is not properly synchronized with a read of the same element.
+struct Element(UnsafeCell<u64>);
+
+impl Element {
+    fn new(value: u64) -> Element {
+        Element(UnsafeCell::new(value))
+    }
+
+    fn get(&self) -> u64 {
+        self.0.with(|ptr| unsafe { *ptr })
+    }
+
+    fn set(&self, value: u64) {
+        self.0.with_mut(|ptr| unsafe { *ptr = value })
+    }
+}
+
+// Only shared between threads through AtomicSlice
+unsafe impl Sync for Element {}
+
+impl Clone for Element {
+    fn clone(&self) -> Element {
+        Element::new(self.get())
+    }
+}
+
+fn write_all<const BUFS: usize>(atomic_slice: &AtomicSlice<Element, BUFS>, value: u64) {
+    atomic_slice.write_with(|slice| {
+        for element in slice {
+            element.set(value);
+        }
+    });
+}
+
+/// Read and check that every element holds the version of the guard
+fn read_versioned<const BUFS: usize>(atomic_slice: &AtomicSlice<Element, BUFS>) {
+    let guard = atomic_slice.read();
+    for element in guard.iter() {
+        assert_eq!(element.get(), guard.version());
+    }
+}
+
+#[test]
+fn loom_read_while_writing() {
+    loom::model(|| {
+        let atomic_slice = Arc::new(AtomicSlice::new(vec![Element::new(0); 2]));
+
+        let writer = {
+            let atomic_slice = Arc::clone(&atomic_slice);
+            thread::spawn(move || {
+                // The second write reuses the partition of the initial data
+                write_all(&atomic_slice, 1);
+                write_all(&atomic_slice, 2);
+            })
+        };
+
+        read_versioned(&atomic_slice);
+
+        writer.join().unwrap();
+        read_versioned(&atomic_slice);
+        assert_eq!(atomic_slice.version(), 2);
+    });
+}
+
+#[test]
+fn loom_competing_writers() {
+    loom::model(|| {
+        let atomic_slice = Arc::new(AtomicSlice::new(vec![Element::new(0); 2]));
+
+        let writers: Vec<_> = [1, 2]
+            .into_iter()
+            .map(|value| {
+                let atomic_slice = Arc::clone(&atomic_slice);
+                thread::spawn(move || write_all(&atomic_slice, value))
+            })
+            .collect();
+
+        {
+            let guard = atomic_slice.read();
+            assert!(guard.iter().all(|e| e.get() == guard[0].get()));
+        }
+
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        let guard = atomic_slice.read();
+        assert_eq!(guard.version(), 2);
+        assert!(guard.iter().all(|e| e.get() == guard[0].get()));
+        assert_ne!(guard[0].get(), 0);
+    });
+}
+
+#[test]
+fn loom_extra_buffers() {
+    loom::model(|| {
+        let atomic_slice = Arc::new(AtomicSlice::<_, 3>::new_with_buffers(vec![
+            Element::new(0);
+            2
+        ]));
+
+        let writer = {
+            let atomic_slice = Arc::clone(&atomic_slice);
+            thread::spawn(move || {
+                for value in 1..=3 {
+                    write_all(&atomic_slice, value);
+                }
+            })
+        };
+
+        read_versioned(&atomic_slice);
+        read_versioned(&atomic_slice);
+
+        writer.join().unwrap();
+        read_versioned(&atomic_slice);
+    });
+}
+
+#[test]
+fn loom_sharded() {
+    loom::model(|| {
+        let atomic_slice = Arc::new(ShardedAtomicSlice::<_, 2>::new(vec![Element::new(0); 2]));
+
+        let writer = {
+            let atomic_slice = Arc::clone(&atomic_slice);
+            thread::spawn(move || {
+                for value in 1..=2 {
+                    atomic_slice.write_with(|slice| {
+                        for element in slice {
+                            element.set(value);
+                        }
+                    });
+                }
+            })
+        };
+
+        {
+            let guard = atomic_slice.read();
+            for element in guard.iter() {
+                assert_eq!(element.get(), guard.version());
+            }
+        }
+
+        writer.join().unwrap();
+    });
+}
+
+#[test]
+fn loom_read_with_previous_while_writing() {
+    loom::model(|| {
+        let atomic_slice = Arc::new(AtomicSlice::<Element, 3>::new_with_buffers(vec![
+            Element::new(0);
+            2
+        ]));
+
+        let writer = {
+            let atomic_slice = Arc::clone(&atomic_slice);
+            thread::spawn(move || {
+                write_all(&atomic_slice, 1);
+                write_all(&atomic_slice, 2);
+            })
+        };
+
+        let pair = atomic_slice.read_with_previous();
+        for guard in [pair.current(), pair.previous()] {
+            for element in guard.iter() {
+                assert_eq!(element.get(), guard.version());
+            }
+        }
+        drop(pair);
+
+        writer.join().unwrap();
+    });
+}
+
+#[test]
+fn loom_parking_writer() {
+    loom::model(|| {
+        let mut atomic_slice = AtomicSlice::new(vec![Element::new(0); 2]);
+        atomic_slice.set_backoff(Backoff::Park);
+        let atomic_slice = Arc::new(atomic_slice);
+
+        let writer = {
+            let atomic_slice = Arc::clone(&atomic_slice);
+            thread::spawn(move || {
+                write_all(&atomic_slice, 1);
+                write_all(&atomic_slice, 2);
+            })
+        };
+
+        read_versioned(&atomic_slice);
+
+        writer.join().unwrap();
+        assert_eq!(atomic_slice.version(), 2);
+    });
+}
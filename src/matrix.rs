@@ -0,0 +1,180 @@
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+use crate::{AtomicSlice, AtomicSliceReadGuard};
+
+/// A two-dimensional variant of `AtomicSlice` holding a matrix with a fixed
+/// number of rows and columns, stored in row-major order. Reading gives
+/// access to individual rows without any manual index math.
+///
+/// ```
+/// use atomicslice::AtomicSlice2D;
+///
+/// let mix = AtomicSlice2D::new(2, 3, vec![0.0; 6]);
+/// mix.write_row(1, &[1.0, 0.5, 0.0]);
+/// assert_eq!(mix.read().row(1), [1.0, 0.5, 0.0]);
+/// ```
+pub struct AtomicSlice2D<T, const BUFS: usize = 2> {
+    inner: AtomicSlice<T, BUFS>,
+    rows: usize,
+    cols: usize,
+}
+
+/// A read lock guard on the data of an `AtomicSlice2D`, which dereferences
+/// to all elements in row-major order and also gives access to single rows.
+pub struct AtomicSlice2DReadGuard<'a, T, const BUFS: usize = 2> {
+    guard: AtomicSliceReadGuard<'a, T, BUFS>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T: Clone> AtomicSlice2D<T> {
+    /// Create a new `AtomicSlice2D` with the given shape from a vector of
+    /// data in row-major order, which must hold `rows * cols` elements,
+    /// otherwise this method panics.
+    pub fn new(rows: usize, cols: usize, data: Vec<T>) -> AtomicSlice2D<T> {
+        AtomicSlice2D::new_with_buffers(rows, cols, data)
+    }
+}
+
+impl<T: Clone, const BUFS: usize> AtomicSlice2D<T, BUFS> {
+    /// Create a new `AtomicSlice2D` with `BUFS` partitions. Otherwise
+    /// identical to `new`.
+    pub fn new_with_buffers(rows: usize, cols: usize, data: Vec<T>) -> AtomicSlice2D<T, BUFS> {
+        if rows.checked_mul(cols) != Some(data.len()) {
+            panic!("Attempted to create AtomicSlice2D from data of the wrong length");
+        }
+        AtomicSlice2D {
+            inner: AtomicSlice::new_with_buffers(data),
+            rows,
+            cols,
+        }
+    }
+
+    /// Write a whole matrix of new data in row-major order. See
+    /// `AtomicSlice::write`.
+    pub fn write(&self, data: &[T]) {
+        self.inner.write(data);
+    }
+
+    /// Write new data to a single row, while all other rows keep their
+    /// currently published contents. The row must exist and the data must
+    /// have one element per column, otherwise this method panics.
+    pub fn write_row(&self, row: usize, data: &[T]) {
+        self.write_rows([(row, data)]);
+    }
+
+    /// Write new data to any number of rows at once, like `write_row`, and
+    /// publish them all together. Later patches to the same row overwrite
+    /// earlier ones.
+    pub fn write_rows<'b, I>(&self, patches: I)
+    where
+        T: 'b,
+        I: IntoIterator<Item = (usize, &'b [T])>,
+        I::IntoIter: Clone,
+    {
+        let patches = patches.into_iter();
+        for (row, data) in patches.clone() {
+            if row >= self.rows || data.len() != self.cols {
+                panic!(
+                    "Attempted to write row of the wrong length or out of bounds to AtomicSlice2D"
+                );
+            }
+        }
+
+        self.inner.update(|previous, slice| {
+            slice.clone_from_slice(previous);
+            for (row, data) in patches {
+                slice[row * self.cols..(row + 1) * self.cols].clone_from_slice(data);
+            }
+        });
+    }
+}
+
+impl<T, const BUFS: usize> AtomicSlice2D<T, BUFS> {
+    /// Get the number of rows
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Get the number of columns
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Get the number of rows and columns
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Get the version of the most recently published data. See
+    /// `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
+    /// Acquire a read lock on the matrix. See `AtomicSlice::read`.
+    pub fn read(&self) -> AtomicSlice2DReadGuard<'_, T, BUFS> {
+        AtomicSlice2DReadGuard {
+            guard: self.inner.read(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+
+    /// Write new data in place, given in row-major order. See
+    /// `AtomicSlice::write_with`.
+    pub fn write_with<F: FnOnce(&mut [T])>(&self, f: F) {
+        self.inner.write_with(f);
+    }
+
+    /// Get the underlying `AtomicSlice`, which holds the data in row-major
+    /// order
+    pub fn as_flat(&self) -> &AtomicSlice<T, BUFS> {
+        &self.inner
+    }
+}
+
+impl<'a, T, const BUFS: usize> Deref for AtomicSlice2DReadGuard<'a, T, BUFS> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.guard
+    }
+}
+
+impl<'a, T, const BUFS: usize> AtomicSlice2DReadGuard<'a, T, BUFS> {
+    /// Get a single row, or panic if it doesn't exist
+    pub fn row(&self, row: usize) -> &[T] {
+        if row >= self.rows {
+            panic!("Attempted to read row out of bounds of AtomicSlice2D");
+        }
+        &self.guard[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// Iterate over all rows
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.rows).map(|row| self.row(row))
+    }
+
+    /// Get the number of rows
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Get the number of columns
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Get the number of rows and columns
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// The version of the data that this guard is holding onto.
+    /// See `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.guard.version()
+    }
+}
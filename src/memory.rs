@@ -0,0 +1,159 @@
+use core::mem::{size_of_val, MaybeUninit};
+use std::io;
+
+#[cfg(all(feature = "mlock", unix))]
+use crate::storage::Storage;
+use crate::AtomicSlice;
+
+/// How the memory holding the partitions of an `AtomicSlice` is prepared
+/// when it is created using `AtomicSlice::new_with_memory`. By default,
+/// nothing special is done, like with `AtomicSlice::new`.
+///
+/// Threads with real-time constraints, such as audio callbacks, must never
+/// take a page fault while reading. `prefault` makes sure that every page
+/// is backed by physical memory up front, and `lock` additionally keeps the
+/// operating system from swapping any of them out later on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryOptions {
+    /// Touch every page of every partition once, so that none of them still
+    /// needs to be faulted in when it is first read from or written to
+    pub prefault: bool,
+    /// Lock every page of every partition into physical memory using
+    /// `mlock`, until the `AtomicSlice` is dropped. This requires the `mlock`
+    /// feature and a Unix-like target, and may fail if the process isn't
+    /// allowed to lock that much memory, see `RLIMIT_MEMLOCK`. The partitions
+    /// are then allocated in whole pages of their own, since unlocking pages
+    /// that are shared with other allocations would unlock those as well.
+    pub lock: bool,
+}
+
+/// The smallest page size of any supported target. Touching one byte every
+/// this many bytes touches every page, whatever the actual page size.
+const PAGE_SIZE: usize = 4096;
+
+impl<T: Clone, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Create a new `AtomicSlice` like `new_with_buffers`, and prepare the
+    /// memory of all of its partitions as described by `options`.
+    ///
+    /// ```
+    /// use atomicslice::{AtomicSlice, MemoryOptions};
+    ///
+    /// let options = MemoryOptions {
+    ///     prefault: true,
+    ///     ..Default::default()
+    /// };
+    /// let atomic_slice: AtomicSlice<f32> =
+    ///     AtomicSlice::new_with_memory(vec![0.0; 4096], options).unwrap();
+    /// ```
+    ///
+    /// Returns an error if locking the memory was asked for and failed, or
+    /// isn't supported.
+    pub fn new_with_memory(
+        data: Vec<T>,
+        options: MemoryOptions,
+    ) -> io::Result<AtomicSlice<T, BUFS>> {
+        #[cfg(all(feature = "mlock", unix))]
+        let mut atomic_slice = if options.lock {
+            AtomicSlice::new_page_aligned(data)
+        } else {
+            AtomicSlice::new_with_buffers(data)
+        };
+        #[cfg(not(all(feature = "mlock", unix)))]
+        let mut atomic_slice = AtomicSlice::new_with_buffers(data);
+        if options.prefault {
+            atomic_slice.prefault();
+        }
+        if options.lock {
+            atomic_slice.lock_memory()?;
+        }
+        Ok(atomic_slice)
+    }
+}
+
+impl<T: Clone, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Create a new `AtomicSlice` like `new_with_buffers`, whose partitions
+    /// take up whole pages that no other allocation shares
+    #[cfg(all(feature = "mlock", unix))]
+    fn new_page_aligned(data: Vec<T>) -> AtomicSlice<T, BUFS> {
+        let stride = data.len();
+        let total = stride
+            .checked_mul(BUFS)
+            .expect("Attempted to create AtomicSlice whose partitions don't fit into memory");
+        let storage = Storage::new_aligned(total, page_size(), |i| data[i % stride].clone());
+        AtomicSlice::from_storage(storage)
+    }
+}
+
+/// The size of the pages that `mlock` locks, which may be larger than
+/// `PAGE_SIZE`
+#[cfg(all(feature = "mlock", unix))]
+pub(crate) fn page_size() -> usize {
+    // Safety: sysconf has no preconditions
+    match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+        size if size > 0 => size as usize,
+        _ => PAGE_SIZE,
+    }
+}
+
+impl<T, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Touch every page of the allocation by writing one of its bytes back
+    /// to itself
+    fn prefault(&mut self) {
+        let data = self.data.as_mut_slice();
+        let len = size_of_val::<[T]>(data);
+        // Bytes are copied as `MaybeUninit` since `T` may have padding
+        let ptr = data.as_mut_ptr() as *mut MaybeUninit<u8>;
+        // The start of the allocation isn't necessarily aligned to a page, so
+        // the last byte may be on a page of its own
+        for offset in (0..len).step_by(PAGE_SIZE).chain(len.checked_sub(1)) {
+            // Safety: the offset is within the allocation, which is borrowed
+            // mutably. Volatile accesses can't be optimized away.
+            unsafe {
+                let byte = ptr.add(offset);
+                byte.write_volatile(byte.read_volatile());
+            }
+        }
+    }
+
+    #[cfg(all(feature = "mlock", unix))]
+    fn lock_memory(&mut self) -> io::Result<()> {
+        let data = self.data.as_mut_slice();
+        let len = size_of_val::<[T]>(data);
+        if len == 0 {
+            return Ok(());
+        }
+        // Safety: the range is exactly the allocation
+        if unsafe { libc::mlock(data.as_ptr().cast(), len) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        self.locked = true;
+        Ok(())
+    }
+
+    #[cfg(not(all(feature = "mlock", unix)))]
+    fn lock_memory(&mut self) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "locking the memory of an AtomicSlice requires the mlock feature on a Unix-like target",
+        ))
+    }
+
+    /// Undo `lock_memory`, if it was called
+    #[cfg(all(feature = "mlock", unix))]
+    pub(crate) fn unlock_memory(&mut self) {
+        if self.locked {
+            let data = self.data.as_mut_slice();
+            // Safety: the range is exactly the allocation that was locked.
+            // Failing to unlock leaves nothing to clean up.
+            unsafe { libc::munlock(data.as_ptr().cast(), size_of_val::<[T]>(data)) };
+            self.locked = false;
+        }
+    }
+}
+
+#[cfg(all(feature = "mlock", unix))]
+impl<T, const BUFS: usize> Drop for AtomicSlice<T, BUFS> {
+    fn drop(&mut self) {
+        self.unlock_memory();
+    }
+}
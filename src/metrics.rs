@@ -0,0 +1,60 @@
+use std::time::{Duration, Instant};
+
+/// How often and for how long writers of an `AtomicSlice` had to wait, as
+/// returned by `AtomicSlice::write_stats`. Only writes that actually had to
+/// wait are timed, so that writes which don't wait aren't slowed down.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WriteStats {
+    /// The number of writes published so far
+    pub writes: u64,
+    /// Waiting for other writers to finish
+    pub lock: WaitStats,
+    /// Waiting for readers to release a partition so that it can be written
+    /// to, which is what long-held read lock guards delay
+    pub drain: WaitStats,
+}
+
+/// How often and for how long writers had to wait for one particular thing.
+/// Part of `WriteStats`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WaitStats {
+    /// The number of writes that had to wait
+    pub waits: u64,
+    /// The number of times writers checked again after waiting, i.e. the
+    /// number of iterations spent spinning, yielding or being woken up
+    pub spins: u64,
+    /// The total time spent waiting
+    pub total: Duration,
+    /// The longest time that a single write spent waiting
+    pub longest: Duration,
+}
+
+impl WriteStats {
+    pub(crate) const fn new() -> WriteStats {
+        WriteStats {
+            writes: 0,
+            lock: WaitStats::new(),
+            drain: WaitStats::new(),
+        }
+    }
+}
+
+impl WaitStats {
+    const fn new() -> WaitStats {
+        WaitStats {
+            waits: 0,
+            spins: 0,
+            total: Duration::ZERO,
+            longest: Duration::ZERO,
+        }
+    }
+
+    /// Record a single wait which began at the given moment
+    pub(crate) fn record(&mut self, spins: u64, since: Instant) {
+        let waited = since.elapsed();
+        self.waits += 1;
+        self.spins += spins;
+        self.total += waited;
+        self.longest = self.longest.max(waited);
+    }
+}
@@ -0,0 +1,94 @@
+use std::{
+    sync::{Condvar, Mutex},
+    task::Waker,
+};
+
+use crate::sync::{wait_forever, AtomicUsize, Ordering, SINGLE_THREADED};
+
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+
+/// Lets threads block (or tasks await) until some condition becomes true,
+/// without costing the notifying thread anything more than an atomic load
+/// while nobody is waiting.
+pub(crate) struct Notifier {
+    num_waiting: AtomicUsize,
+    /// Wakers of tasks that are awaiting the next notification
+    wakers: Mutex<Vec<Waker>>,
+    condvar: Condvar,
+}
+
+impl Notifier {
+    pub(crate) const fn new() -> Notifier {
+        Notifier {
+            num_waiting: AtomicUsize::new(0),
+            wakers: Mutex::new(Vec::new()),
+            condvar: Condvar::new(),
+        }
+    }
+
+    /// Block the current thread until `done` returns true. The condition is
+    /// re-checked every time `notify_all` is called. Whatever `done` depends
+    /// on must be updated before calling `notify_all`.
+    pub(crate) fn wait_until<F: FnMut() -> bool>(&self, mut done: F) {
+        if done() {
+            return;
+        }
+        if SINGLE_THREADED {
+            wait_forever();
+        }
+
+        let mut lock = self.wakers.lock().unwrap();
+        self.num_waiting.fetch_add(1, Ordering::SeqCst);
+        // The notifying thread either sees that this thread is waiting and
+        // must then acquire the mutex before notifying, or it made its
+        // changes early enough that they are visible here already.
+        while !done() {
+            lock = self.condvar.wait(lock).unwrap();
+        }
+        self.num_waiting.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// The asynchronous counterpart to `wait_until`, to be called from
+    /// `Future::poll`. If the condition is not yet true, the task's waker
+    /// is registered to be woken by the next call to `notify_all`.
+    #[cfg(feature = "async")]
+    pub(crate) fn poll_until<F: FnMut() -> bool>(
+        &self,
+        cx: &mut Context<'_>,
+        mut done: F,
+    ) -> Poll<()> {
+        if done() {
+            return Poll::Ready(());
+        }
+
+        let mut wakers = self.wakers.lock().unwrap();
+        if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+            self.num_waiting.fetch_add(1, Ordering::SeqCst);
+        }
+        // Same reasoning as in wait_until
+        if done() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Wake up all threads that are waiting in `wait_until` and all tasks
+    /// that were registered in `poll_until`
+    pub(crate) fn notify_all(&self) {
+        if self.num_waiting.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+        let mut lock = self.wakers.lock().unwrap();
+        self.condvar.notify_all();
+
+        let wakers = std::mem::take(&mut *lock);
+        self.num_waiting.fetch_sub(wakers.len(), Ordering::SeqCst);
+        drop(lock);
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
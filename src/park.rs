@@ -0,0 +1,42 @@
+use std::{sync::Mutex, thread::Thread};
+
+/// Threads that are parked until `unpark_all` is called with the address of
+/// the status word that they are waiting on. Read guards only know their
+/// status word, so this can't be stored per `AtomicSlice`. It is only ever
+/// touched while a writer is parked, which is rare enough for a single global
+/// lock to be good enough.
+static PARKED: Mutex<Vec<(usize, Thread)>> = Mutex::new(Vec::new());
+
+/// Register the current thread to be unparked by `unpark_all` with any of
+/// the given keys, replacing any earlier registrations of the current thread.
+/// This must be called before checking whatever condition the thread will
+/// be parked on, so that it can't miss being woken up.
+#[cfg(not(any(loom, shuttle)))]
+pub(crate) fn register(keys: impl Iterator<Item = usize>) {
+    let current = std::thread::current();
+    let mut parked = PARKED.lock().unwrap();
+    parked.retain(|(_, thread)| thread.id() != current.id());
+    parked.extend(keys.map(|key| (key, current.clone())));
+}
+
+/// Remove all registrations of the current thread
+#[cfg(not(any(loom, shuttle)))]
+pub(crate) fn unregister() {
+    let current = std::thread::current().id();
+    PARKED
+        .lock()
+        .unwrap()
+        .retain(|(_, thread)| thread.id() != current);
+}
+
+/// Wake up all threads that are registered with the given key
+pub(crate) fn unpark_all(key: usize) {
+    PARKED.lock().unwrap().retain(|(k, thread)| {
+        if *k == key {
+            thread.unpark();
+            false
+        } else {
+            true
+        }
+    });
+}
@@ -0,0 +1,33 @@
+use bytemuck::Pod;
+
+use crate::{AtomicSlice, AtomicSliceReadGuard, LengthMismatch};
+
+impl<'a, T: Pod, const BUFS: usize> AtomicSliceReadGuard<'a, T, BUFS> {
+    /// View the locked data as raw bytes, such as for uploading it to a GPU
+    /// or sending it over the network without copying it first.
+    pub fn as_bytes(&self) -> &[u8] {
+        bytemuck::cast_slice(self.slice)
+    }
+}
+
+impl<T: Pod, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Write new data given as raw bytes, which are copied into place as-is.
+    /// The bytes don't need to be aligned for `T`, but there must be exactly
+    /// as many of them as the `AtomicSlice` holds, otherwise nothing is written
+    /// and an error is returned whose lengths are counted in bytes.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn write_bytes(&self, bytes: &[u8]) -> Result<(), LengthMismatch> {
+        let expected = self.stride * core::mem::size_of::<T>();
+        if bytes.len() != expected {
+            return Err(LengthMismatch {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+
+        self.write_with(|slice| bytemuck::cast_slice_mut(slice).copy_from_slice(bytes));
+        Ok(())
+    }
+}
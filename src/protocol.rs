@@ -0,0 +1,910 @@
+use core::cell::UnsafeCell;
+
+#[cfg(feature = "metrics")]
+use crate::metrics::{WaitStats, WriteStats};
+use crate::{
+    backoff::{Backoff, Waiter},
+    status::{Layout, Status},
+    sync::{
+        wait_forever, AtomicStatus, AtomicU32, AtomicU64, CachePadded, Ordering, SINGLE_THREADED,
+    },
+};
+#[cfg(feature = "std")]
+use crate::{
+    notify::Notifier,
+    sync::{fence, AtomicBool},
+};
+
+/// The synchronization state shared by all types that are built on the
+/// same read and write protocol as `AtomicSlice`, independently of how and
+/// where the `BUFS` copies of the actual data are stored.
+///
+/// Reading the data of a slice is synchronized with writing it through the
+/// status word alone: acquiring a read lock is an `Acquire` operation which
+/// synchronizes with the `Release` operation that published the slice, and
+/// releasing a read lock is a `Release` operation which synchronizes with
+/// the writer's `Acquire` load that finds the slice to be free again. Since
+/// every other operation on the status word is a read-modify-write, these
+/// are always part of the same release sequence. Competing writers are
+/// synchronized with each other through a ticket lock made of `next_ticket`
+/// and `now_serving`, which lets them in first come, first served.
+///
+/// New readers only ever lock the active slice, except for a moment when
+/// they race with a write being published. A writer waiting for the readers
+/// of another slice thus only waits for the read locks that were taken
+/// while it was still active, and for clones of their guards, no matter
+/// how many new readers keep overlapping them.
+///
+/// The status word, which is modified by every reader, and the writer lock,
+/// which competing writers may be spinning on, each get a cache line of their
+/// own so that neither slows down the other.
+///
+/// The status word can also be split into `SHARDS` copies, each of which
+/// counts the readers of only some of the threads. These all point readers
+/// to the same slice, except briefly while a write is being published, and a
+/// slice is only free once no copy points to it or counts any of its readers.
+pub(crate) struct Protocol<const BUFS: usize, const SHARDS: usize = 1> {
+    status: [CachePadded<AtomicStatus>; SHARDS],
+    /// The ticket that the next writer to wait for the write portion draws
+    next_ticket: CachePadded<AtomicU32>,
+    /// The ticket of the writer holding the write portion. The write portion
+    /// is unlocked while this equals `next_ticket`.
+    now_serving: CachePadded<AtomicU32>,
+    /// The version of the most recently published data
+    version: AtomicU64,
+    /// The version of the data in each slice, or `NO_VERSION`. Like the data
+    /// itself, this is only written to by the writer while the slice is not
+    /// in use
+    slice_versions: UnsafeCell<[u64; BUFS]>,
+    /// Wakes up threads waiting for a newer version
+    #[cfg(feature = "std")]
+    pub(crate) notifier: Notifier,
+    /// Whether a writer panicked while holding the write portion
+    #[cfg(feature = "std")]
+    poisoned: AtomicBool,
+    /// How writers wait for each other and for readers
+    backoff: Backoff,
+    /// Wakes up writers parked until the write portion is unlocked
+    #[cfg(feature = "std")]
+    unlocked: Notifier,
+    /// How often and for how long writers had to wait. Only ever touched
+    /// while holding the write portion, except to be read or reset.
+    #[cfg(feature = "metrics")]
+    stats: std::sync::Mutex<WriteStats>,
+}
+
+/// Exclusive access to the write portion, which is released when dropped.
+/// If it is dropped while panicking, the protocol is poisoned, since the
+/// slice being written to may have been left partially written.
+pub(crate) struct WriteLock<'a, const BUFS: usize, const SHARDS: usize> {
+    protocol: &'a Protocol<BUFS, SHARDS>,
+    /// Whether this lock holds a ticket, which must be passed on
+    unlock: bool,
+    /// When the write portion was locked, to trace how long it was held
+    #[cfg(feature = "tracing")]
+    locked_at: std::time::Instant,
+}
+
+impl<'a, const BUFS: usize, const SHARDS: usize> Drop for WriteLock<'a, BUFS, SHARDS> {
+    fn drop(&mut self) {
+        #[cfg(feature = "std")]
+        if std::thread::panicking() {
+            self.protocol.poisoned.store(true, Ordering::Relaxed);
+            #[cfg(feature = "tracing")]
+            tracing::warn!("writer panicked, poisoning the AtomicSlice");
+        }
+        if self.unlock {
+            self.protocol.unlock_writer();
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(held = ?self.locked_at.elapsed(), "writer finished");
+    }
+}
+
+// The slice versions are protected by the protocol itself
+unsafe impl<const BUFS: usize, const SHARDS: usize> Sync for Protocol<BUFS, SHARDS> {}
+
+/// Acquire an additional read lock on a slice which is already locked
+pub(crate) fn retain<const BUFS: usize>(status: &AtomicStatus, slice: u8) {
+    // The slice can't be written to while the existing lock is held, so there
+    // is nothing to synchronize with
+    let previous = status.fetch_add(Layout::<BUFS>::inc(slice), Ordering::Relaxed);
+    paranoid_assert!(
+        Layout::<BUFS>::valid(previous),
+        "Corrupted AtomicSlice status word {:#x}",
+        previous
+    );
+    paranoid_assert!(
+        Layout::<BUFS>::use_count(slice, previous) > 0,
+        "Retained a read lock on AtomicSlice partition {} which had no readers",
+        slice
+    );
+
+    if Layout::<BUFS>::use_count(slice, previous) >= Layout::<BUFS>::MAX_COUNT {
+        undo_acquire::<BUFS>(status, Layout::<BUFS>::inc(slice));
+        too_many_reads();
+    }
+
+    #[cfg(all(debug_assertions, feature = "std", not(any(loom, shuttle))))]
+    crate::registry::acquired(status, slice);
+    #[cfg(feature = "tracing-reads")]
+    tracing::trace!(slice, "acquired read lock");
+}
+
+/// Panic because acquiring another read lock would overflow a use count.
+///
+/// The offending increment spills only into the padding above the count, and
+/// is undone before panicking, so that every other reader and writer carries
+/// on unaffected.
+#[cold]
+#[inline(never)]
+fn too_many_reads() -> ! {
+    panic!("Too many simultaneous reads of AtomicSlice");
+}
+
+/// Release a read lock on the given slice, as previously acquired
+/// by `Protocol::acquire`
+pub(crate) fn release<const BUFS: usize>(status: &AtomicStatus, slice: u8) {
+    // All reads of the slice must happen before the writer reuses it
+    let previous = status.fetch_sub(Layout::<BUFS>::inc(slice), Ordering::Release);
+    paranoid_assert!(
+        Layout::<BUFS>::valid(previous),
+        "Corrupted AtomicSlice status word {:#x}",
+        previous
+    );
+    paranoid_assert!(
+        Layout::<BUFS>::use_count(slice, previous) > 0,
+        "Released a read lock on AtomicSlice partition {} which had no readers",
+        slice
+    );
+
+    #[cfg(all(debug_assertions, feature = "std", not(any(loom, shuttle))))]
+    crate::registry::released(status, slice);
+    #[cfg(feature = "tracing-reads")]
+    tracing::trace!(slice, "released read lock");
+
+    #[cfg(feature = "std")]
+    if previous & Layout::<BUFS>::WRITER_PARKED != 0 {
+        crate::park::unpark_all((status as *const AtomicStatus).addr());
+    }
+}
+
+/// The version of slices which hold no published data, such as those that
+/// are being written to, or that a write panicked halfway through
+const NO_VERSION: u64 = u64::MAX;
+
+/// Take back the given increment of the use counts of slices that were
+/// never read from, such as after guessing the wrong slice. A writer may
+/// have seen the temporary count and parked in the meantime, and has to be
+/// woken up again just like after releasing a read lock.
+pub(crate) fn undo_acquire<const BUFS: usize>(status: &AtomicStatus, inc: Status) {
+    // There is nothing to synchronize with, since nothing was read
+    let previous = status.fetch_sub(inc, Ordering::Relaxed);
+    paranoid_assert!(
+        Layout::<BUFS>::valid(previous),
+        "Corrupted AtomicSlice status word {:#x}",
+        previous
+    );
+
+    #[cfg(feature = "std")]
+    if previous & Layout::<BUFS>::WRITER_PARKED != 0 {
+        crate::park::unpark_all((status as *const AtomicStatus).addr());
+    }
+}
+
+impl<const BUFS: usize, const SHARDS: usize> Protocol<BUFS, SHARDS> {
+    /// Evaluating this fails to compile if `SHARDS` is not supported
+    const CHECK: () = assert!(SHARDS >= 1, "At least one shard is needed");
+
+    #[cfg(not(loom))]
+    pub(crate) const fn new() -> Protocol<BUFS, SHARDS> {
+        #[allow(clippy::let_unit_value)]
+        let () = Layout::<BUFS>::CHECK;
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CHECK;
+
+        Protocol {
+            status: [const { CachePadded::new(AtomicStatus::new(0)) }; SHARDS],
+            next_ticket: CachePadded::new(AtomicU32::new(0)),
+            now_serving: CachePadded::new(AtomicU32::new(0)),
+            version: AtomicU64::new(0),
+            slice_versions: UnsafeCell::new([0; BUFS]),
+            #[cfg(feature = "std")]
+            notifier: Notifier::new(),
+            #[cfg(feature = "std")]
+            poisoned: AtomicBool::new(false),
+            backoff: Backoff::DEFAULT,
+            #[cfg(feature = "std")]
+            unlocked: Notifier::new(),
+            #[cfg(feature = "metrics")]
+            stats: std::sync::Mutex::new(WriteStats::new()),
+        }
+    }
+
+    /// Loom's atomics can't be created in constant contexts
+    #[cfg(loom)]
+    pub(crate) fn new() -> Protocol<BUFS, SHARDS> {
+        #[allow(clippy::let_unit_value)]
+        let () = Layout::<BUFS>::CHECK;
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CHECK;
+
+        Protocol {
+            status: core::array::from_fn(|_| CachePadded::new(AtomicStatus::new(0))),
+            next_ticket: CachePadded::new(AtomicU32::new(0)),
+            now_serving: CachePadded::new(AtomicU32::new(0)),
+            version: AtomicU64::new(0),
+            slice_versions: UnsafeCell::new([0; BUFS]),
+            #[cfg(feature = "std")]
+            notifier: Notifier::new(),
+            #[cfg(feature = "std")]
+            poisoned: AtomicBool::new(false),
+            backoff: Backoff::DEFAULT,
+            #[cfg(feature = "std")]
+            unlocked: Notifier::new(),
+            #[cfg(feature = "metrics")]
+            stats: std::sync::Mutex::new(WriteStats::new()),
+        }
+    }
+
+    /// Get the version of the most recently published data
+    pub(crate) fn version(&self) -> u64 {
+        // Sequentially consistent so that the notifier can't miss an update
+        // between checking the version and going to sleep
+        self.version.load(Ordering::SeqCst)
+    }
+
+    /// Block until a version newer than `since_version` has been published
+    /// and return that version
+    #[cfg(feature = "std")]
+    pub(crate) fn wait_for_update(&self, since_version: u64) -> u64 {
+        let mut version = self.version();
+        self.notifier.wait_until(|| {
+            version = self.version();
+            version > since_version
+        });
+        version
+    }
+
+    /// The status word of the first shard, which is the only one unless
+    /// the protocol is sharded
+    pub(crate) fn status(&self) -> &AtomicStatus {
+        &self.status[0]
+    }
+
+    /// The status word of the given shard, wrapping around if there are
+    /// fewer shards
+    pub(crate) fn shard(&self, shard: usize) -> &AtomicStatus {
+        &self.status[shard % SHARDS]
+    }
+
+    /// Acquire a read lock on the current slice through the first shard and
+    /// return its index. The lock must later be released using `release`.
+    pub(crate) fn acquire(&self) -> u8 {
+        Self::acquire_in(self.status())
+    }
+
+    /// Like `acquire`, but returns `None` instead of panicking if the current
+    /// slice already has as many readers as its use count can hold
+    pub(crate) fn try_acquire(&self) -> Option<u8> {
+        Self::try_acquire_in(self.status())
+    }
+
+    /// Acquire a read lock on the current slice through the given shard's
+    /// status word and return its index. The lock must later be released
+    /// using `release` on the same status word.
+    pub(crate) fn acquire_in(status: &AtomicStatus) -> u8 {
+        match Self::try_acquire_in(status) {
+            Some(slice) => slice,
+            None => too_many_reads(),
+        }
+    }
+
+    /// Like `acquire_in`, but returns `None` instead of panicking if the
+    /// current slice already has as many readers as its use count can hold
+    pub(crate) fn try_acquire_in(status: &AtomicStatus) -> Option<u8> {
+        let slice = Self::try_acquire_unregistered(status)?;
+        #[cfg(all(debug_assertions, feature = "std", not(any(loom, shuttle))))]
+        crate::registry::acquired(status, slice);
+        #[cfg(feature = "tracing-reads")]
+        tracing::trace!(slice, "acquired read lock");
+        Some(slice)
+    }
+
+    /// `try_acquire_in` without keeping track of the lock in debug builds
+    fn try_acquire_unregistered(status: &AtomicStatus) -> Option<u8> {
+        // Optimistically assume that the active slice doesn't change before
+        // it is locked, which is almost always the case, and lock only that
+        let guess = Layout::<BUFS>::active_slice(status.load(Ordering::Relaxed));
+        let active = match Self::try_acquire_guessed(status, guess) {
+            Ok(slice) => return slice,
+            Err(active) => active,
+        };
+
+        // A write was published in the meantime. Guess again, this time the
+        // slice that was just seen to be active. Unlike locking every slice,
+        // this leaves alone the slices that the next writer may be waiting
+        // for readers to leave, and no write can be published while it waits,
+        // so that a steady stream of new readers can't hold it up.
+        match Self::try_acquire_guessed(status, active) {
+            Ok(slice) => slice,
+            Err(_) => Self::try_acquire_all(status),
+        }
+    }
+
+    /// Acquire a read lock on the given slice if it is the active one, or
+    /// return the index of the actually active slice otherwise. Returns
+    /// `Ok(None)` if the slice already has as many readers as its use count
+    /// can hold.
+    fn try_acquire_guessed(status: &AtomicStatus, guess: u8) -> Result<Option<u8>, u8> {
+        // This synchronizes with the publishing of the guessed slice
+        let previous = status.fetch_add(Layout::<BUFS>::inc(guess), Ordering::Acquire);
+        paranoid_assert!(
+            Layout::<BUFS>::valid(previous),
+            "Corrupted AtomicSlice status word {:#x}",
+            previous
+        );
+
+        let active = Layout::<BUFS>::active_slice(previous);
+        let saturated = Layout::<BUFS>::use_count(guess, previous) >= Layout::<BUFS>::MAX_COUNT;
+        if active == guess && !saturated {
+            return Ok(Some(guess));
+        }
+
+        // The guess was wrong, or the guessed slice has too many readers
+        // already
+        undo_acquire::<BUFS>(status, Layout::<BUFS>::inc(guess));
+
+        if active == guess {
+            Ok(None)
+        } else {
+            Err(active)
+        }
+    }
+
+    /// Acquire a read lock on the current slice without guessing, which
+    /// takes one more atomic operation than a correct guess but succeeds
+    /// unless the current slice has too many readers
+    fn try_acquire_all(status: &AtomicStatus) -> Option<u8> {
+        // Get current slice index while also marking all slices as in use.
+        // This synchronizes with the publishing of the current slice.
+        let previous = status.fetch_add(Layout::<BUFS>::INC_ALL, Ordering::Acquire);
+
+        paranoid_assert!(
+            Layout::<BUFS>::valid(previous),
+            "Corrupted AtomicSlice status word {:#x}",
+            previous
+        );
+
+        let current_slice = Layout::<BUFS>::active_slice(previous);
+        let saturated =
+            Layout::<BUFS>::use_count(current_slice, previous) >= Layout::<BUFS>::MAX_COUNT;
+
+        paranoid_assert!(
+            Layout::<BUFS>::use_count(current_slice, status.load(Ordering::Relaxed)) > 0,
+            "Lost the read lock on AtomicSlice partition {} while acquiring it",
+            current_slice
+        );
+
+        // Now that the current slice is known, mark the others as no longer in use
+        let inc_other_slices = Layout::<BUFS>::INC_ALL - Layout::<BUFS>::inc(current_slice);
+        undo_acquire::<BUFS>(status, inc_other_slices);
+
+        paranoid_assert!(
+            Layout::<BUFS>::use_count(current_slice, status.load(Ordering::Relaxed)) > 0,
+            "Lost the read lock on AtomicSlice partition {} while acquiring it",
+            current_slice
+        );
+
+        // The other slices' counts may have briefly overflowed into their
+        // padding as well, but only the current slice's count is kept
+        if saturated {
+            undo_acquire::<BUFS>(status, Layout::<BUFS>::inc(current_slice));
+            return None;
+        }
+
+        Some(current_slice)
+    }
+
+    /// Acquire read locks on both the current slice and the most recently
+    /// published slice before it, and return both their indices. Unlike the
+    /// current slice, the previous one may be written to at any moment, so
+    /// the write portion is locked briefly to keep writers out. Both locks
+    /// must later be released using `release` on the first shard.
+    pub(crate) fn acquire_with_previous(&self) -> (u8, u8) {
+        let lock = self.lock_writer();
+
+        // Without any earlier data, the current data is the previous data too
+        let current_slice = self.acquire();
+        let previous_slice = (0..BUFS as u8)
+            .filter(|&i| i != current_slice)
+            .filter(|&i| unsafe { self.slice_version(i) } != NO_VERSION)
+            .max_by_key(|&i| unsafe { self.slice_version(i) })
+            .unwrap_or(current_slice);
+
+        if !self.try_acquire_locked(previous_slice) {
+            // Unlock first, so that panicking doesn't poison anything
+            drop(lock);
+            release::<BUFS>(self.status(), current_slice);
+            too_many_reads();
+        }
+
+        (current_slice, previous_slice)
+    }
+
+    /// Acquire a read lock through the first shard on the slice holding the
+    /// version that was published `n` versions before the current one, if
+    /// any slice still holds it, and return its index. Like with
+    /// `acquire_with_previous`, the write portion is locked briefly.
+    pub(crate) fn acquire_back(&self, n: u64) -> Option<u8> {
+        let lock = self.lock_writer();
+
+        // Slices holding no published data never match, since `NO_VERSION`
+        // is never reached by actual versions
+        let version = self.version().checked_sub(n)?;
+        let slice = (0..BUFS as u8).find(|&i| unsafe { self.slice_version(i) } == version)?;
+
+        if !self.try_acquire_locked(slice) {
+            drop(lock);
+            too_many_reads();
+        }
+        Some(slice)
+    }
+
+    /// Acquire a read lock on any slice through the first shard while
+    /// holding exclusive access to the write portion, or return false if the
+    /// slice already has as many readers as its use count can hold.
+    fn try_acquire_locked(&self, slice: u8) -> bool {
+        // Holding the write portion synchronizes with the writer that
+        // published the slice, and the next writer will see it in use
+        // before picking a slice to write to
+        let status = self.status();
+        let previous = status.fetch_add(Layout::<BUFS>::inc(slice), Ordering::Relaxed);
+        paranoid_assert!(
+            Layout::<BUFS>::valid(previous),
+            "Corrupted AtomicSlice status word {:#x}",
+            previous
+        );
+        if Layout::<BUFS>::use_count(slice, previous) >= Layout::<BUFS>::MAX_COUNT {
+            undo_acquire::<BUFS>(status, Layout::<BUFS>::inc(slice));
+            return false;
+        }
+
+        #[cfg(all(debug_assertions, feature = "std", not(any(loom, shuttle))))]
+        crate::registry::acquired(status, slice);
+        #[cfg(feature = "tracing-reads")]
+        tracing::trace!(slice, "acquired read lock");
+        true
+    }
+
+    /// Get the version of the data in one of the slices.
+    ///
+    /// Safety: the slice must not be written to concurrently, i.e. the
+    /// caller must either hold a read lock on it or hold exclusive access
+    /// to the write portion.
+    pub(crate) unsafe fn slice_version(&self, slice: u8) -> u64 {
+        (*self.slice_versions.get())[slice as usize]
+    }
+
+    /// Attempt to gain exclusive access to the write portion, which lasts
+    /// until the returned lock is dropped. This only succeeds if no other
+    /// writer holds it or is waiting for it.
+    pub(crate) fn try_lock_writer(&self) -> Option<WriteLock<'_, BUFS, SHARDS>> {
+        // This synchronizes with the previous writer's unlock_writer
+        let ticket = self.now_serving.load(Ordering::Acquire);
+        // Draw the ticket being served, if nobody else has drawn it yet
+        self.next_ticket
+            .compare_exchange(
+                ticket,
+                ticket.wrapping_add(1),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            )
+            .ok()?;
+        Some(self.write_lock())
+    }
+
+    /// Wait for exclusive access to the write portion, which lasts until the
+    /// returned lock is dropped. Competing writers are let in in the order
+    /// in which they started waiting.
+    pub(crate) fn lock_writer(&self) -> WriteLock<'_, BUFS, SHARDS> {
+        let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+        // This synchronizes with the previous writer's unlock_writer
+        if self.now_serving.load(Ordering::Acquire) == ticket {
+            return self.write_lock();
+        }
+
+        #[cfg(any(feature = "metrics", feature = "tracing"))]
+        let since = std::time::Instant::now();
+        let (lock, _spins) = self.wait_for_writer_lock(ticket);
+        #[cfg(feature = "metrics")]
+        self.record_wait(|stats| &mut stats.lock, _spins, since);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(waited = ?since.elapsed(), spins = _spins, "writer waited for other writers");
+        lock
+    }
+
+    /// The slow path of `lock_writer`, which waits for the given ticket to
+    /// be served and also returns how many times it checked again after
+    /// waiting
+    fn wait_for_writer_lock(&self, ticket: u32) -> (WriteLock<'_, BUFS, SHARDS>, u64) {
+        let mut spins = 0;
+
+        // Like when waiting for a free slice, this only spins and yields
+        // under loom and shuttle
+        #[cfg(all(feature = "std", not(any(loom, shuttle))))]
+        if self.backoff == Backoff::Park {
+            self.unlocked.wait_until(|| {
+                // Pairs with the fence in unlock_writer, so that either this
+                // sees its ticket being served or the unlocking writer sees
+                // this one waiting
+                fence(Ordering::SeqCst);
+                spins += 1;
+                self.now_serving.load(Ordering::Acquire) == ticket
+            });
+            return (self.write_lock(), spins);
+        }
+
+        let mut waiter = self.waiter();
+        loop {
+            waiter.wait();
+            spins += 1;
+            if self.now_serving.load(Ordering::Acquire) == ticket {
+                return (self.write_lock(), spins);
+            }
+        }
+    }
+
+    /// The number of writers holding or waiting for the write portion
+    #[cfg(all(test, not(any(loom, shuttle))))]
+    pub(crate) fn writers(&self) -> u32 {
+        let now_serving = self.now_serving.load(Ordering::Relaxed);
+        self.next_ticket
+            .load(Ordering::Relaxed)
+            .wrapping_sub(now_serving)
+    }
+
+    /// Whether a writer holds the write portion
+    pub(crate) fn is_writing(&self) -> bool {
+        let now_serving = self.now_serving.load(Ordering::Relaxed);
+        self.next_ticket.load(Ordering::Relaxed) != now_serving
+    }
+
+    /// The lock of a writer whose ticket is being served
+    fn write_lock(&self) -> WriteLock<'_, BUFS, SHARDS> {
+        WriteLock {
+            protocol: self,
+            unlock: true,
+            #[cfg(feature = "tracing")]
+            locked_at: std::time::Instant::now(),
+        }
+    }
+
+    /// Choose how writers wait for each other and for readers
+    pub(crate) const fn set_backoff(&mut self, backoff: Backoff) {
+        self.backoff = backoff;
+    }
+
+    /// How writers wait for each other and for readers
+    pub(crate) fn backoff(&self) -> Backoff {
+        self.backoff
+    }
+
+    /// Start waiting for something according to the chosen backoff policy
+    pub(crate) fn waiter(&self) -> Waiter {
+        Waiter::new(self.backoff)
+    }
+
+    /// Claim exclusive access to the write portion without locking it, only
+    /// so that a panic while writing poisons the protocol like it would with
+    /// a real lock.
+    ///
+    /// Safety: there must be no other writer which could lock the write
+    /// portion for as long as the returned lock is alive.
+    #[cfg(target_has_atomic = "ptr")]
+    pub(crate) unsafe fn assume_writer(&self) -> WriteLock<'_, BUFS, SHARDS> {
+        WriteLock {
+            protocol: self,
+            unlock: false,
+            #[cfg(feature = "tracing")]
+            locked_at: std::time::Instant::now(),
+        }
+    }
+
+    /// Whether a writer panicked while holding the write portion
+    #[cfg(feature = "std")]
+    pub(crate) fn is_poisoned(&self) -> bool {
+        self.poisoned.load(Ordering::Relaxed)
+    }
+
+    /// Forget that a writer panicked while holding the write portion
+    #[cfg(feature = "std")]
+    pub(crate) fn clear_poison(&self) {
+        self.poisoned.store(false, Ordering::Relaxed);
+    }
+
+    /// Release exclusive access to the write portion and pass it on to the
+    /// writer holding the next ticket, if any
+    fn unlock_writer(&self) {
+        // Only the writer holding the write portion changes this
+        let ticket = self.now_serving.load(Ordering::Relaxed);
+        paranoid_assert!(
+            ticket != self.next_ticket.load(Ordering::Relaxed),
+            "Unlocked the write portion of an AtomicSlice which wasn't locked"
+        );
+        self.now_serving
+            .store(ticket.wrapping_add(1), Ordering::Release);
+
+        #[cfg(feature = "std")]
+        if self.backoff == Backoff::Park {
+            fence(Ordering::SeqCst);
+            self.unlocked.notify_all();
+        }
+    }
+
+    /// The index of the slice that new readers are currently pointed to.
+    /// Only stays meaningful while holding exclusive access to the write
+    /// portion.
+    pub(crate) fn current_slice(&self) -> u8 {
+        // Only writers change the active slice index, and they are already
+        // synchronized with each other. All shards agree outside of publish.
+        let status = self.status().load(Ordering::Relaxed);
+        paranoid_assert!(
+            Layout::<BUFS>::valid(status),
+            "Corrupted AtomicSlice status word {:#x}",
+            status
+        );
+        Layout::<BUFS>::active_slice(status)
+    }
+
+    /// Find a slice which is not currently being pointed to by readers and
+    /// which no readers are holding onto anymore, and which can thus be
+    /// written to next. If there are several, one holding no data is chosen,
+    /// or otherwise the one holding the oldest data. Only meaningful while
+    /// holding exclusive access to the write portion.
+    fn find_free_slice(&self) -> Option<u8> {
+        let in_use = self.slices_in_use();
+        (0..BUFS as u8)
+            .filter(|&i| in_use & (1 << i) == 0)
+            .min_by_key(|&i| unsafe { self.slice_version(i) }.wrapping_add(1))
+    }
+
+    /// Find a free slice like `find_free_slice` and claim it for writing, so
+    /// that its old data is forgotten even if the write never completes.
+    /// Must be called while holding exclusive access to the write portion.
+    pub(crate) fn free_slice(&self) -> Option<u8> {
+        let slice = self.find_free_slice()?;
+        self.invalidate(slice);
+        Some(slice)
+    }
+
+    /// Mark the given slice as holding no published data, so that it is
+    /// never read from before the next write to it is published. Must be
+    /// called while holding exclusive access to the write portion, on a
+    /// slice that isn't in use.
+    fn invalidate(&self, slice: u8) {
+        unsafe {
+            (*self.slice_versions.get())[slice as usize] = NO_VERSION;
+        }
+    }
+
+    /// Mark every slice but the active one as holding no published data,
+    /// for when their contents aren't known
+    pub(crate) fn invalidate_inactive(&mut self) {
+        let current_slice = self.current_slice();
+        for (i, version) in self.slice_versions.get_mut().iter_mut().enumerate() {
+            if i != current_slice as usize {
+                *version = NO_VERSION;
+            }
+        }
+    }
+
+    /// The number of readers holding onto each slice, summed over all shards
+    pub(crate) fn reader_counts(&self) -> [usize; BUFS] {
+        let mut counts = [0; BUFS];
+        for status in &self.status {
+            let status = status.load(Ordering::Relaxed);
+            paranoid_assert!(
+                Layout::<BUFS>::valid(status),
+                "Corrupted AtomicSlice status word {:#x}",
+                status
+            );
+            for (i, count) in counts.iter_mut().enumerate() {
+                *count += Layout::<BUFS>::use_count(i as u8, status) as usize;
+            }
+        }
+        counts
+    }
+
+    /// One bit for each slice that is active or still being read from
+    fn slices_in_use(&self) -> u8 {
+        let mut in_use: u8 = 0;
+        for status in &self.status {
+            // This synchronizes with the release of the last read lock on the
+            // slices that are found to be free
+            let status = status.load(Ordering::Acquire);
+            paranoid_assert!(
+                Layout::<BUFS>::valid(status),
+                "Corrupted AtomicSlice status word {:#x}",
+                status
+            );
+            in_use |= 1 << Layout::<BUFS>::active_slice(status);
+            for i in 0..BUFS as u8 {
+                if Layout::<BUFS>::use_count(i, status) > 0 {
+                    in_use |= 1 << i;
+                }
+            }
+        }
+        in_use
+    }
+
+    /// Wait until the given slice, which must not be the active one, is no
+    /// longer being read from. Only meaningful while holding exclusive access
+    /// to the write portion.
+    #[cfg(feature = "zeroize")]
+    pub(crate) fn wait_until_unused(&self, slice: u8) {
+        let mut waiter = self.waiter();
+        while self.slices_in_use() & (1 << slice) != 0 {
+            waiter.wait();
+        }
+    }
+
+    /// Wait until `free_slice` finds and claims a slice to write to
+    pub(crate) fn wait_for_free_slice(&self) -> u8 {
+        if let Some(i) = self.free_slice() {
+            return i;
+        }
+
+        #[cfg(all(debug_assertions, feature = "std", not(any(loom, shuttle))))]
+        self.check_not_reading();
+        if SINGLE_THREADED {
+            wait_forever();
+        }
+
+        #[cfg(any(feature = "metrics", feature = "tracing"))]
+        let since = std::time::Instant::now();
+        let (next_i, _spins) = self.wait_for_readers();
+        #[cfg(feature = "metrics")]
+        self.record_wait(|stats| &mut stats.drain, _spins, since);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            waited = ?since.elapsed(),
+            spins = _spins,
+            slice = next_i,
+            "writer waited for readers to release a slice"
+        );
+        self.invalidate(next_i);
+        next_i
+    }
+
+    /// The slow path of `wait_for_free_slice`, which also returns how many
+    /// times the slices were checked again after waiting
+    fn wait_for_readers(&self) -> (u8, u64) {
+        // Neither loom nor shuttle can see parked threads, so they only get to
+        // spin and yield
+        #[cfg(all(feature = "std", not(any(loom, shuttle))))]
+        if self.backoff == Backoff::Park {
+            return self.park_for_free_slice();
+        }
+
+        let mut spins = 0;
+        let mut waiter = self.waiter();
+        loop {
+            waiter.wait();
+            spins += 1;
+            if let Some(i) = self.find_free_slice() {
+                return (i, spins);
+            }
+        }
+    }
+
+    /// Panic if every slice that could be written to next is held onto by a
+    /// read lock of the current thread, which would never be released while
+    /// the thread waits for it
+    #[cfg(all(debug_assertions, feature = "std", not(any(loom, shuttle))))]
+    fn check_not_reading(&self) {
+        let current_slice = self.current_slice();
+        let held_by_this_thread = (0..BUFS as u8).filter(|&i| i != current_slice).all(|i| {
+            self.status
+                .iter()
+                .any(|status| crate::registry::holds(status, i))
+        });
+        if held_by_this_thread {
+            panic!(
+                "Attempted to write to an AtomicSlice while holding a read lock on it \
+                in the same thread, which would wait forever"
+            );
+        }
+    }
+
+    /// Park the current thread until `find_free_slice` finds a slice to write to
+    #[cfg(all(feature = "std", not(any(loom, shuttle))))]
+    fn park_for_free_slice(&self) -> (u8, u64) {
+        let mut spins = 0;
+        let keys = || {
+            self.status
+                .iter()
+                .map(|status| (&**status as *const AtomicStatus).addr())
+        };
+        let next_i = loop {
+            // Every reader that releases a slice after the flag is set will
+            // see it and wake this thread up, and every reader that released
+            // one before is seen by free_slice
+            crate::park::register(keys());
+            for status in &self.status {
+                status.fetch_or(Layout::<BUFS>::WRITER_PARKED, Ordering::Relaxed);
+            }
+            if let Some(i) = self.find_free_slice() {
+                break i;
+            }
+            std::thread::park();
+            spins += 1;
+        };
+
+        for status in &self.status {
+            status.fetch_and(!Layout::<BUFS>::WRITER_PARKED, Ordering::Relaxed);
+        }
+        crate::park::unregister();
+        (next_i, spins)
+    }
+
+    /// Add a wait which began at the given moment to the statistics
+    #[cfg(feature = "metrics")]
+    fn record_wait<F: FnOnce(&mut WriteStats) -> &mut WaitStats>(
+        &self,
+        f: F,
+        spins: u64,
+        since: std::time::Instant,
+    ) {
+        f(&mut self.stats.lock().unwrap()).record(spins, since);
+    }
+
+    /// How often and for how long writers had to wait so far
+    #[cfg(feature = "metrics")]
+    pub(crate) fn write_stats(&self) -> WriteStats {
+        *self.stats.lock().unwrap()
+    }
+
+    /// Start counting anew
+    #[cfg(feature = "metrics")]
+    pub(crate) fn reset_write_stats(&self) {
+        *self.stats.lock().unwrap() = WriteStats::new();
+    }
+
+    /// Point all new readers to the given slice, which must have just been
+    /// written to while holding exclusive access to the write portion
+    pub(crate) fn publish(&self, slice: u8) {
+        #[cfg(feature = "metrics")]
+        {
+            self.stats.lock().unwrap().writes += 1;
+        }
+
+        let version = self.version.load(Ordering::Relaxed) + 1;
+        unsafe {
+            (*self.slice_versions.get())[slice as usize] = version;
+        }
+
+        // Only writers ever change the active slice index, so flipping the
+        // bits that differ between the current and next index sets it
+        let current_slice = self.current_slice();
+        for status in &self.status {
+            let status = status.fetch_xor((current_slice ^ slice) as Status, Ordering::Release);
+            paranoid_assert!(
+                Layout::<BUFS>::valid(status),
+                "Corrupted AtomicSlice status word {:#x}",
+                status
+            );
+            paranoid_assert!(
+                Layout::<BUFS>::active_slice(status) == current_slice,
+                "Another writer published to AtomicSlice partition {} concurrently",
+                Layout::<BUFS>::active_slice(status)
+            );
+        }
+
+        self.version.store(version, Ordering::SeqCst);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(version, slice, "published");
+        #[cfg(feature = "std")]
+        self.notifier.notify_all();
+    }
+}
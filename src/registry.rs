@@ -0,0 +1,111 @@
+//! Keeps track of the read locks held by each thread in debug builds, so that
+//! a thread which is about to wait for its own read locks to be released
+//! while writing can panic instead.
+
+use std::{
+    sync::{Mutex, MutexGuard, PoisonError},
+    thread::ThreadId,
+    vec::Vec,
+};
+
+use crate::sync::AtomicStatus;
+
+/// A read lock that hasn't been released yet
+struct Entry {
+    /// The address of the status word through which the lock was acquired
+    key: usize,
+    slice: u8,
+    /// The thread that acquired the lock
+    thread: ThreadId,
+    /// Whether the lock is known to be held still. A lock released by
+    /// another thread than the one that acquired it could belong to any
+    /// thread holding a lock on the same slice, so if there are several,
+    /// their locks on that slice can no longer be told apart.
+    certain: bool,
+}
+
+/// Every read lock that hasn't been released yet. Each release removes one
+/// entry, so that this never holds more entries than there are read locks.
+static HELD: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+
+std::thread_local! {
+    static THREAD: ThreadId = std::thread::current().id();
+}
+
+fn held() -> MutexGuard<'static, Vec<Entry>> {
+    // The list is never left inconsistent, since nothing in here panics
+    HELD.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn key(status: &AtomicStatus) -> usize {
+    (status as *const AtomicStatus).addr()
+}
+
+/// The current thread, unless it is shutting down
+fn current_thread() -> Option<ThreadId> {
+    THREAD.try_with(|thread| *thread).ok()
+}
+
+/// Record that the current thread acquired a read lock on the given slice
+pub(crate) fn acquired(status: &AtomicStatus, slice: u8) {
+    // Locks taken while the thread is shutting down can't be tracked anymore,
+    // and are forgotten about again when they are released
+    if let Some(thread) = current_thread() {
+        held().push(Entry {
+            key: key(status),
+            slice,
+            thread,
+            certain: true,
+        });
+    }
+}
+
+/// Record that the current thread released a read lock on the given slice,
+/// which may have been acquired by another thread, such as when a guard is
+/// handed to a scoped thread
+pub(crate) fn released(status: &AtomicStatus, slice: u8) {
+    let key = key(status);
+    let thread = current_thread();
+    let mut held = held();
+    let on_slice = |e: &Entry| e.key == key && e.slice == slice;
+
+    let own = held
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| on_slice(e) && Some(e.thread) == thread)
+        .min_by_key(|(_, e)| e.certain)
+        .map(|(i, _)| i);
+    if let Some(i) = own {
+        held.swap_remove(i);
+        return;
+    }
+
+    let Some(i) = held.iter().position(on_slice) else {
+        return;
+    };
+    let owner = held.swap_remove(i).thread;
+    if held.iter().any(|e| on_slice(e) && e.thread != owner) {
+        held.iter_mut()
+            .filter(|e| on_slice(e))
+            .for_each(|e| e.certain = false);
+    }
+}
+
+/// Whether the current thread is known to hold a read lock on the given slice
+pub(crate) fn holds(status: &AtomicStatus, slice: u8) -> bool {
+    let key = key(status);
+    let Some(thread) = current_thread() else {
+        return false;
+    };
+    held()
+        .iter()
+        .any(|e| e.key == key && e.slice == slice && e.thread == thread && e.certain)
+}
+
+/// The number of read locks acquired by the current thread that haven't been
+/// released yet
+#[cfg(test)]
+pub(crate) fn held_count() -> usize {
+    let thread = current_thread();
+    held().iter().filter(|e| Some(e.thread) == thread).count()
+}
@@ -0,0 +1,132 @@
+use alloc::vec::Vec;
+
+use zeroize::Zeroize;
+
+use crate::{storage::Storage, AtomicSlice, AtomicSliceReadGuard, Backoff};
+
+/// A variant of `AtomicSlice` for secret data such as keys or tokens, which
+/// doesn't leave stale copies of it lying around in memory. After every
+/// write, the partitions holding older data are wiped as soon as the last
+/// reader is done with them, and all partitions are wiped when the
+/// `ZeroizingAtomicSlice` is dropped.
+///
+/// ```
+/// use atomicslice::ZeroizingAtomicSlice;
+///
+/// let key = ZeroizingAtomicSlice::new(vec![0u8; 32]);
+/// key.write(&[42; 32]);
+/// assert_eq!(*key.read(), [42; 32]);
+/// ```
+///
+/// Since wiping the older data means waiting for its readers, writing blocks
+/// for as long as any reader holds onto a lock guard for the previous data.
+/// Copies of the data that readers make themselves are of course not wiped.
+pub struct ZeroizingAtomicSlice<T: Zeroize, const BUFS: usize = 2> {
+    inner: AtomicSlice<T, BUFS>,
+}
+
+impl<T: Zeroize + Clone> ZeroizingAtomicSlice<T> {
+    /// Create a new `ZeroizingAtomicSlice` from a vector of data, which is
+    /// wiped after being copied. The `ZeroizingAtomicSlice` will have the
+    /// length of this vector for its entire lifetime.
+    pub fn new(data: Vec<T>) -> ZeroizingAtomicSlice<T> {
+        ZeroizingAtomicSlice::new_with_buffers(data)
+    }
+}
+
+impl<T: Zeroize + Clone, const BUFS: usize> ZeroizingAtomicSlice<T, BUFS> {
+    /// Create a new `ZeroizingAtomicSlice` with `BUFS` partitions. Otherwise
+    /// identical to `new`.
+    pub fn new_with_buffers(mut data: Vec<T>) -> ZeroizingAtomicSlice<T, BUFS> {
+        let stride = data.len();
+        let total = stride
+            .checked_mul(BUFS)
+            .expect("Attempted to create AtomicSlice whose partitions don't fit into memory");
+        // Allocate all partitions up front, since growing or shrinking the
+        // allocation would leave a copy of the data behind that can't be wiped
+        let mut partitions = Vec::with_capacity(total);
+        partitions.extend_from_slice(&data);
+        data.iter_mut().for_each(Zeroize::zeroize);
+        for _ in 1..BUFS {
+            partitions.extend_from_within(..stride);
+        }
+        let inner = AtomicSlice::from_storage(Storage::new(partitions.into_boxed_slice()));
+        let zeroizing = ZeroizingAtomicSlice { inner };
+        // Only the active partition needs to hold the data
+        let _lock = zeroizing.inner.protocol.lock_writer();
+        zeroizing.scrub_stale();
+        drop(_lock);
+        zeroizing
+    }
+
+    /// Write a slice of new data and wipe the older data. See
+    /// `AtomicSlice::write`.
+    pub fn write(&self, data: &[T]) {
+        if data.len() != self.inner.len() {
+            panic!("Attempted to write slice of the wrong length to ZeroizingAtomicSlice");
+        }
+
+        self.write_with(|slice| slice.clone_from_slice(data));
+    }
+}
+
+impl<T: Zeroize, const BUFS: usize> ZeroizingAtomicSlice<T, BUFS> {
+    /// Get the number of elements
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if the slice has no elements
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Get the version of the most recently published data. See
+    /// `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
+    /// Acquire a read lock on the slice. See `AtomicSlice::read`.
+    pub fn read(&self) -> AtomicSliceReadGuard<'_, T, BUFS> {
+        self.inner.read()
+    }
+
+    /// Choose how writers wait. See `AtomicSlice::set_backoff`.
+    pub fn set_backoff(&mut self, backoff: Backoff) {
+        self.inner.set_backoff(backoff);
+    }
+
+    /// Write new data in place and wipe the older data. See
+    /// `AtomicSlice::write_with`. Unlike there, the partition being written
+    /// to has always been wiped already, so the function receives a slice of
+    /// zeroed elements.
+    pub fn write_with<F: FnOnce(&mut [T])>(&self, f: F) {
+        let _lock = self.inner.protocol.lock_writer();
+        unsafe { self.inner.write_exclusive(f) };
+        self.scrub_stale();
+    }
+
+    /// Wait for the readers of every partition but the active one to be done
+    /// and wipe them. Must be called while holding the write portion.
+    fn scrub_stale(&self) {
+        let current_slice = self.inner.protocol.current_slice();
+        for i in (0..BUFS as u8).filter(|&i| i != current_slice) {
+            self.inner.protocol.wait_until_unused(i);
+            // Safety: the write portion is locked and the slice is not in use
+            unsafe { self.inner.slice_mut(i) }
+                .iter_mut()
+                .for_each(Zeroize::zeroize);
+        }
+    }
+}
+
+impl<T: Zeroize, const BUFS: usize> Drop for ZeroizingAtomicSlice<T, BUFS> {
+    fn drop(&mut self) {
+        self.inner
+            .data
+            .as_mut_slice()
+            .iter_mut()
+            .for_each(Zeroize::zeroize);
+    }
+}
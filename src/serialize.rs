@@ -0,0 +1,22 @@
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::AtomicSlice;
+
+/// Serializes a snapshot of the current contents as a sequence, taken under
+/// a single read lock
+impl<T: Serialize, const BUFS: usize> Serialize for AtomicSlice<T, BUFS> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.read().iter())
+    }
+}
+
+/// Deserializes a sequence into a new `AtomicSlice` of the same length
+impl<'de, T: Deserialize<'de> + Clone, const BUFS: usize> Deserialize<'de>
+    for AtomicSlice<T, BUFS>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(AtomicSlice::new_with_buffers)
+    }
+}
@@ -0,0 +1,199 @@
+use alloc::vec::Vec;
+use core::panic::{RefUnwindSafe, UnwindSafe};
+
+use crate::{protocol::Protocol, storage::Storage, AtomicSliceReadGuard, Backoff, TryWriteError};
+
+/// A variant of `AtomicSlice` for very many reader threads, which spreads
+/// the readers' use counts across `SHARDS` separate status words that each
+/// live on their own cache line. Every thread always reads through the same
+/// shard, so that threads in different shards never contend with each other.
+///
+/// Reading is exactly as cheap as with an `AtomicSlice`, and scales much
+/// better once dozens of threads are reading at the same time. In return,
+/// writers need to inspect and update every shard, which makes writing
+/// slower. The API is otherwise the same as that of an `AtomicSlice` with
+/// two partitions.
+///
+/// With the `std` feature, threads are assigned to shards in a round-robin
+/// fashion the first time they read. Without it, the shard is derived from
+/// the address of the current stack instead.
+pub struct ShardedAtomicSlice<T, const SHARDS: usize> {
+    data: Storage<T>,
+    stride: usize,
+    protocol: Protocol<2, SHARDS>,
+}
+
+/// The stripe of the current thread, which decides its shard
+#[cfg(feature = "std")]
+fn current_stripe() -> usize {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static NEXT_STRIPE: AtomicUsize = AtomicUsize::new(0);
+
+    std::thread_local! {
+        static STRIPE: usize = NEXT_STRIPE.fetch_add(1, Ordering::Relaxed);
+    }
+
+    STRIPE.with(|stripe| *stripe)
+}
+
+/// The stripe of the current thread, which decides its shard
+#[cfg(not(feature = "std"))]
+fn current_stripe() -> usize {
+    // Different threads (or interrupt handlers with their own stacks) are
+    // very likely to use stacks that are at least a few kilobytes apart
+    let marker = 0_u8;
+    core::ptr::addr_of!(marker).addr() >> 12
+}
+
+impl<T: Clone, const SHARDS: usize> ShardedAtomicSlice<T, SHARDS> {
+    /// Create a new `ShardedAtomicSlice` from a vector of data, for example
+    /// using `ShardedAtomicSlice::<f32, 16>::new(data)`. It will have the
+    /// length of this vector for its entire lifetime.
+    pub fn new(mut data: Vec<T>) -> ShardedAtomicSlice<T, SHARDS> {
+        let protocol = Protocol::new();
+
+        let stride = data.len();
+        // Fill the other partition with a copy of the initial data
+        data.extend_from_within(..stride);
+        ShardedAtomicSlice {
+            data: Storage::new(data.into_boxed_slice()),
+            stride,
+            protocol,
+        }
+    }
+
+    /// Write a slice of new data. The given slice must have the same length as
+    /// the `ShardedAtomicSlice` itself, otherwise this method panics.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn write(&self, data: &[T]) {
+        if data.len() != self.stride {
+            panic!("Attempted to write slice of the wrong length to ShardedAtomicSlice");
+        }
+
+        self.write_with(|slice| slice.clone_from_slice(data));
+    }
+
+    /// Attempt to write a slice of new data without waiting. See
+    /// `AtomicSlice::try_write`.
+    pub fn try_write(&self, data: &[T]) -> Result<(), TryWriteError> {
+        if data.len() != self.stride {
+            panic!("Attempted to write slice of the wrong length to ShardedAtomicSlice");
+        }
+
+        let Some(_lock) = self.protocol.try_lock_writer() else {
+            return Err(TryWriteError::WriteInProgress);
+        };
+
+        let Some(next_i) = self.protocol.free_slice() else {
+            return Err(TryWriteError::ReadInProgress);
+        };
+
+        unsafe { self.slice_mut(next_i) }.clone_from_slice(data);
+
+        self.protocol.publish(next_i);
+        Ok(())
+    }
+}
+
+impl<T, const SHARDS: usize> ShardedAtomicSlice<T, SHARDS> {
+    /// Get the number of elements
+    pub fn len(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns true if the slice has no elements
+    pub fn is_empty(&self) -> bool {
+        self.stride == 0
+    }
+
+    /// Get the version of the most recently published data. See
+    /// `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.protocol.version()
+    }
+
+    /// Acquire a read lock on the slice through the current thread's shard.
+    /// See `AtomicSlice::read`.
+    pub fn read(&self) -> AtomicSliceReadGuard<'_, T> {
+        let status = self.protocol.shard(current_stripe());
+        let current_slice = Protocol::<2, SHARDS>::acquire_in(status);
+        let slice: &[T] = unsafe { self.slice(current_slice) };
+        let version = unsafe { self.protocol.slice_version(current_slice) };
+
+        AtomicSliceReadGuard {
+            slice,
+            current_slice,
+            status,
+            version,
+        }
+    }
+
+    /// Block the current thread until a version newer than `since_version`
+    /// has been published. See `AtomicSlice::wait_for_update`.
+    #[cfg(feature = "std")]
+    pub fn wait_for_update(&self, since_version: u64) -> u64 {
+        self.protocol.wait_for_update(since_version)
+    }
+
+    /// Choose how writers wait. See `AtomicSlice::set_backoff`.
+    pub fn set_backoff(&mut self, backoff: Backoff) {
+        self.protocol.set_backoff(backoff);
+    }
+
+    /// Returns true if a thread panicked while writing. See
+    /// `AtomicSlice::is_poisoned`.
+    #[cfg(feature = "std")]
+    pub fn is_poisoned(&self) -> bool {
+        self.protocol.is_poisoned()
+    }
+
+    /// Clear the poisoned state after a panic while writing. See
+    /// `AtomicSlice::clear_poison`.
+    #[cfg(feature = "std")]
+    pub fn clear_poison(&self) {
+        self.protocol.clear_poison();
+    }
+
+    /// Write new data in place. See `AtomicSlice::write_with`.
+    pub fn write_with<F: FnOnce(&mut [T])>(&self, f: F) {
+        let _lock = self.protocol.lock_writer();
+
+        // Wait to ensure the next slice is not being used in any shard
+        let next_i = self.protocol.wait_for_free_slice();
+
+        // Let the caller fill in the next slice
+        f(unsafe { self.slice_mut(next_i) });
+
+        self.protocol.publish(next_i);
+    }
+
+    /// Get shared access to one of the slices.
+    ///
+    /// Safety: the slice must not be written to while the returned reference
+    /// is alive, i.e. the caller must either hold a read lock on it or hold
+    /// exclusive access to the write portion.
+    unsafe fn slice(&self, slice: u8) -> &[T] {
+        let offset = (slice as usize) * self.stride;
+        let ptr_begin = self.data.as_ptr().add(offset);
+        core::slice::from_raw_parts(ptr_begin, self.stride)
+    }
+
+    /// Get mutable access to one of the slices.
+    ///
+    /// Safety: the caller must hold exclusive access to the write portion
+    /// and the slice must not be in use by any readers.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn slice_mut(&self, slice: u8) -> &mut [T] {
+        let offset = (slice as usize) * self.stride;
+        let ptr_begin = self.data.as_ptr().add(offset);
+        core::slice::from_raw_parts_mut(ptr_begin, self.stride)
+    }
+}
+
+unsafe impl<T: Send + Sync, const SHARDS: usize> Sync for ShardedAtomicSlice<T, SHARDS> {}
+unsafe impl<T: Send, const SHARDS: usize> Send for ShardedAtomicSlice<T, SHARDS> {}
+impl<T: RefUnwindSafe, const SHARDS: usize> RefUnwindSafe for ShardedAtomicSlice<T, SHARDS> {}
+impl<T: UnwindSafe, const SHARDS: usize> UnwindSafe for ShardedAtomicSlice<T, SHARDS> {}
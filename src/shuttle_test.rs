@@ -0,0 +1,82 @@
+//! Tests of the read and write protocol under randomized schedules. Run these
+//! using `cargo test --release --lib --features shuttle`. A failing schedule
+//! is printed along with the panic and can be replayed using
+//! `shuttle::replay`.
+
+use shuttle::{scheduler::RandomScheduler, sync::Arc, thread, Config, Runner};
+
+use crate::{AtomicSlice, Backoff};
+
+/// Explore the given test under this many random schedules
+const ITERATIONS: usize = 1000;
+
+/// Run the given test under random schedules chosen using a fixed seed, so
+/// that every run explores the same schedules
+fn check<F: Fn() + Send + Sync + 'static>(f: F) {
+    let mut config = Config::new();
+    // Only sequential consistency is modelled, which is fine for these tests
+    config.silence_warnings = true;
+    let scheduler = RandomScheduler::new_from_seed(0x5eed, ITERATIONS);
+    Runner::new(scheduler, config).run(f);
+}
+
+/// Read and check that every element equals the version of the guard
+fn read_versioned<const BUFS: usize>(atomic_slice: &AtomicSlice<u64, BUFS>) {
+    let guard = atomic_slice.read();
+    for element in guard.iter() {
+        assert_eq!(*element, guard.version());
+    }
+}
+
+#[test]
+fn shuttle_read_while_writing() {
+    check(|| {
+        let atomic_slice = Arc::new(AtomicSlice::new(vec![0; 4]));
+
+        let writer = {
+            let atomic_slice = Arc::clone(&atomic_slice);
+            thread::spawn(move || {
+                for value in 1..=3 {
+                    atomic_slice.write(&[value; 4]);
+                }
+            })
+        };
+
+        for _ in 0..3 {
+            read_versioned(&atomic_slice);
+        }
+
+        writer.join().unwrap();
+        read_versioned(&atomic_slice);
+        assert_eq!(atomic_slice.version(), 3);
+    });
+}
+
+#[test]
+fn shuttle_competing_writers() {
+    check(|| {
+        let mut atomic_slice = AtomicSlice::<_, 3>::new_with_buffers(vec![0; 4]);
+        atomic_slice.set_backoff(Backoff::Park);
+        let atomic_slice = Arc::new(atomic_slice);
+
+        let writers: Vec<_> = (0..2)
+            .map(|_| {
+                let atomic_slice = Arc::clone(&atomic_slice);
+                thread::spawn(move || {
+                    atomic_slice.update(|current, next| {
+                        for (next, current) in next.iter_mut().zip(current) {
+                            *next = current + 1;
+                        }
+                    });
+                })
+            })
+            .collect();
+
+        read_versioned(&atomic_slice);
+
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        assert_eq!(*atomic_slice.read(), [2; 4]);
+    });
+}
@@ -0,0 +1,102 @@
+use alloc::sync::Arc;
+use core::cell::UnsafeCell;
+
+use crate::{
+    sync::{AtomicBool, Ordering},
+    AtomicSlice,
+};
+
+/// The most recent snapshot handed out by `AtomicSlice::snapshot_arc`, along
+/// with the version of the data it holds. This is only ever looked at by
+/// whichever thread manages to claim it, and threads that find it busy simply
+/// go without, so that taking a snapshot never waits.
+pub(crate) struct SnapshotCache<T> {
+    busy: AtomicBool,
+    cached: UnsafeCell<Option<(u64, Arc<[T]>)>>,
+}
+
+impl<T> SnapshotCache<T> {
+    pub(crate) fn new() -> SnapshotCache<T> {
+        SnapshotCache {
+            busy: AtomicBool::new(false),
+            cached: UnsafeCell::new(None),
+        }
+    }
+
+    /// Forget the cached snapshot, such as when the data it was copied from
+    /// is changed in place without publishing a new version
+    pub(crate) fn clear(&mut self) {
+        *self.cached.get_mut() = None;
+    }
+
+    /// Get the cached snapshot if it holds the given version
+    fn get(&self, version: u64) -> Option<Arc<[T]>> {
+        self.try_with(|cached| match cached {
+            Some((v, snapshot)) if *v == version => Some(Arc::clone(snapshot)),
+            _ => None,
+        })
+        .flatten()
+    }
+
+    /// Cache the given snapshot unless a newer one is cached already
+    fn put(&self, version: u64, snapshot: &Arc<[T]>) {
+        let replaced = self.try_with(|cached| match cached {
+            Some((v, _)) if *v >= version => None,
+            _ => cached.replace((version, Arc::clone(snapshot))),
+        });
+        // Dropping the elements of the previous snapshot may run arbitrary
+        // code, which must not happen while the cache is claimed
+        drop(replaced);
+    }
+
+    /// Claim the cache and pass it to the given function, which must not
+    /// panic, or return `None` if another thread is using it
+    fn try_with<R, F: FnOnce(&mut Option<(u64, Arc<[T]>)>) -> R>(&self, f: F) -> Option<R> {
+        self.busy
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()?;
+        // Safety: no other thread can access the cache until it is released
+        let result = f(unsafe { &mut *self.cached.get() });
+        self.busy.store(false, Ordering::Release);
+        Some(result)
+    }
+}
+
+impl<T: Clone, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Get a snapshot of the current contents which can be shared freely and
+    /// read without any locking. The contents are only copied into a new
+    /// `Arc` if a write was published since the last snapshot was taken, and
+    /// otherwise the same `Arc` as last time is returned, so that many readers
+    /// asking for snapshots of rarely changing data share a single copy.
+    ///
+    /// Like `read`, this never waits. If another thread is busy updating the
+    /// cached snapshot at the same moment, a fresh copy is returned instead.
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use atomicslice::AtomicSlice;
+    ///
+    /// let atomic_slice = AtomicSlice::new(vec![1, 2, 3]);
+    /// let first = atomic_slice.snapshot_arc();
+    /// assert!(Arc::ptr_eq(&first, &atomic_slice.snapshot_arc()));
+    ///
+    /// atomic_slice.write(&[4, 5, 6]);
+    /// let second = atomic_slice.snapshot_arc();
+    /// assert_eq!(*first, [1, 2, 3]);
+    /// assert_eq!(*second, [4, 5, 6]);
+    /// ```
+    pub fn snapshot_arc(&self) -> Arc<[T]> {
+        if let Some(snapshot) = self.snapshot.get(self.version()) {
+            return snapshot;
+        }
+
+        let guard = self.read();
+        let snapshot: Arc<[T]> = Arc::from(&*guard);
+        let version = guard.version();
+        drop(guard);
+
+        self.snapshot.put(version, &snapshot);
+        snapshot
+    }
+}
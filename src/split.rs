@@ -0,0 +1,187 @@
+use alloc::{sync::Arc, vec::Vec};
+
+use crate::{AtomicSlice, AtomicSliceReadGuard, TryWriteError};
+
+/// A handle for reading from an `AtomicSlice` that was split using
+/// `AtomicSlice::split`. Readers can be cloned freely and sent to other
+/// threads, but can never write.
+pub struct Reader<T, const BUFS: usize = 2> {
+    inner: Arc<AtomicSlice<T, BUFS>>,
+}
+
+/// The one and only handle for writing to an `AtomicSlice` that was split
+/// using `AtomicSlice::split`. Because no other thread can possibly be
+/// writing at the same time, writes only ever need to wait for readers
+/// of the inactive partition.
+///
+/// The writer also owns a scratch buffer, which holds the most recently
+/// published data and can be changed at leisure through `scratch_mut` before
+/// being published using `publish`. Producers that change only parts of the
+/// data from one version to the next thus need neither a buffer of their own
+/// nor a copy of the published data before each change.
+///
+/// ```
+/// use atomicslice::AtomicSlice;
+///
+/// let (reader, mut writer) = AtomicSlice::new(vec![0; 4]).split();
+/// writer.scratch_mut()[1] = 10;
+/// writer.publish();
+/// writer.scratch_mut()[3] = 30;
+/// writer.publish();
+/// assert_eq!(*reader.read(), [0, 10, 0, 30]);
+/// ```
+pub struct Writer<T, const BUFS: usize = 2> {
+    inner: Arc<AtomicSlice<T, BUFS>>,
+    /// A copy of the most recently published data plus any changes made to
+    /// it since, or empty if it needs to be copied anew
+    scratch: Vec<T>,
+}
+
+pub(crate) fn split<T, const BUFS: usize>(
+    atomic_slice: AtomicSlice<T, BUFS>,
+) -> (Reader<T, BUFS>, Writer<T, BUFS>) {
+    let inner = Arc::new(atomic_slice);
+    (
+        Reader {
+            inner: Arc::clone(&inner),
+        },
+        Writer {
+            inner,
+            scratch: Vec::new(),
+        },
+    )
+}
+
+impl<T, const BUFS: usize> Reader<T, BUFS> {
+    /// Get the number of elements
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if the slice has no elements
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Acquire a read lock on the slice. See `AtomicSlice::read`.
+    pub fn read(&self) -> AtomicSliceReadGuard<'_, T, BUFS> {
+        self.inner.read()
+    }
+}
+
+impl<T, const BUFS: usize> Clone for Reader<T, BUFS> {
+    fn clone(&self) -> Self {
+        Reader {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T, const BUFS: usize> Writer<T, BUFS> {
+    /// Get the number of elements
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if the slice has no elements
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Acquire a read lock on the slice. See `AtomicSlice::read`.
+    pub fn read(&self) -> AtomicSliceReadGuard<'_, T, BUFS> {
+        self.inner.read()
+    }
+
+    /// Returns true if a thread panicked while writing. See
+    /// `AtomicSlice::is_poisoned`.
+    #[cfg(feature = "std")]
+    pub fn is_poisoned(&self) -> bool {
+        self.inner.is_poisoned()
+    }
+
+    /// Clear the poisoned state after a panic while writing. See
+    /// `AtomicSlice::clear_poison`.
+    #[cfg(feature = "std")]
+    pub fn clear_poison(&self) {
+        self.inner.clear_poison();
+    }
+
+    /// Write new data in place. See `AtomicSlice::write_with`.
+    pub fn write_with<F: FnOnce(&mut [T])>(&mut self, f: F) {
+        // The scratch buffer is refreshed from the new data when needed again
+        self.scratch.clear();
+        // Safety: this is the only writer
+        unsafe {
+            let _lock = self.inner.protocol.assume_writer();
+            self.inner.write_exclusive(f);
+        }
+    }
+}
+
+impl<T: Clone, const BUFS: usize> Writer<T, BUFS> {
+    /// Write a slice of new data. The given slice must have the same length as
+    /// the `AtomicSlice` itself, otherwise this method panics.
+    ///
+    /// This method may block if any readers are holding lock guards to the
+    /// inactive partition for extended periods of time.
+    pub fn write(&mut self, data: &[T]) {
+        if data.len() != self.len() {
+            panic!("Attempted to write slice of the wrong length to AtomicSlice");
+        }
+
+        self.write_with(|slice| slice.clone_from_slice(data));
+    }
+
+    /// Attempt to write a slice of new data without waiting for readers of
+    /// the inactive partition. See `AtomicSlice::try_write`. Since there are
+    /// no other writers, the only possible error is
+    /// `TryWriteError::ReadInProgress`.
+    pub fn try_write(&mut self, data: &[T]) -> Result<(), TryWriteError> {
+        if data.len() != self.len() {
+            panic!("Attempted to write slice of the wrong length to AtomicSlice");
+        }
+
+        let Some(next_i) = self.inner.protocol.free_slice() else {
+            return Err(TryWriteError::ReadInProgress);
+        };
+
+        // Safety: this is the only writer and the slice is not in use
+        let _lock = unsafe { self.inner.protocol.assume_writer() };
+        unsafe { self.inner.slice_mut(next_i) }.clone_from_slice(data);
+
+        self.inner.protocol.publish(next_i);
+        self.scratch.clear();
+        Ok(())
+    }
+
+    /// Get mutable access to the scratch buffer, which holds the most
+    /// recently published data plus whatever changes were made to it through
+    /// this method since. Nothing is published until `publish` is called.
+    ///
+    /// The buffer is allocated and filled in when it is first used, and
+    /// again after writing through any other method, but its allocation is
+    /// reused from then on.
+    pub fn scratch_mut(&mut self) -> &mut [T] {
+        if self.scratch.is_empty() {
+            // This is the only writer, so the data can't change in between
+            self.scratch.extend_from_slice(&self.inner.read());
+        }
+        &mut self.scratch
+    }
+
+    /// Publish the contents of the scratch buffer, which are kept for further
+    /// changes. This only copies the data once, into the inactive partition.
+    ///
+    /// This method may block if any readers are holding lock guards to the
+    /// inactive partition for extended periods of time.
+    pub fn publish(&mut self) {
+        self.scratch_mut();
+        // Safety: this is the only writer
+        unsafe {
+            let _lock = self.inner.protocol.assume_writer();
+            self.inner
+                .write_exclusive(|slice| slice.clone_from_slice(&self.scratch));
+        }
+    }
+}
@@ -0,0 +1,129 @@
+/// The integer type of the status word. This is `u64` by default, `u32`
+/// with the `status-u32` feature, or `u128` with the `status-u128` feature.
+#[cfg(not(any(feature = "status-u32", feature = "status-u128")))]
+pub type Status = u64;
+
+/// The integer type of the status word. This is `u64` by default, `u32`
+/// with the `status-u32` feature, or `u128` with the `status-u128` feature.
+#[cfg(feature = "status-u32")]
+pub type Status = u32;
+
+/// The integer type of the status word. This is `u64` by default, `u32`
+/// with the `status-u32` feature, or `u128` with the `status-u128` feature.
+#[cfg(feature = "status-u128")]
+pub type Status = u128;
+
+/// The bit layout of the status word of an `AtomicSlice` with `BUFS` slices.
+///
+/// The lowest bits hold the index of the currently active slice, followed
+/// by a flag that is set while a writer is parked waiting for readers. With
+/// a 64-bit or 128-bit status word, these take up the entire lowest byte, with
+/// the flag in its highest bit. With a 32-bit status word, they take up only
+/// as many bits as are needed for `BUFS` slices plus the flag.
+/// The remaining upper bits are divided evenly between the slices' use counts,
+/// starting from the top, with each count being followed by at least one bit
+/// of padding so that an overflowing count can be detected instead of silently
+/// spilling into its neighbour. Fields are at most 24 bits wide with counts
+/// of at most 16 bits, so that two slices produce exactly the layout described
+/// by `constants`. In a 128-bit status word, fields are at most 40 bits wide
+/// with counts of at most 32 bits instead. More slices leave fewer bits for
+/// each use count, which limits the number of simultaneous reads:
+///
+/// | `BUFS` | count bits | max simultaneous reads | with `status-u32` | with `status-u128` |
+/// |--------|------------|------------------------|-------------------|--------------------|
+/// | 2      | 16         | 65535                  | 16383             | 4294967295         |
+/// | 3      | 16         | 65535                  | 255               | 4294967295         |
+/// | 4      | 13         | 8191                   | 63                | 536870911          |
+/// | 5      | 10         | 1023                   | 15                | 8388607            |
+/// | 6      | 8          | 255                    | 7                 | 524287             |
+/// | 7      | 7          | 127                    | 7                 | 65535              |
+/// | 8      | 6          | 63                     | 3                 | 16383              |
+pub(crate) struct Layout<const BUFS: usize>;
+
+impl<const BUFS: usize> Layout<BUFS> {
+    /// Evaluating this fails to compile if `BUFS` is not supported
+    pub(crate) const CHECK: () = assert!(
+        BUFS >= 2 && BUFS <= 8,
+        "AtomicSlice supports between 2 and 8 buffers"
+    );
+
+    #[cfg(not(feature = "status-u32"))]
+    const INDEX_BITS: u32 = 8;
+
+    #[cfg(feature = "status-u32")]
+    const INDEX_BITS: u32 = usize::BITS - (BUFS - 1).leading_zeros() + 1;
+
+    #[cfg(not(feature = "status-u128"))]
+    const MAX_FIELD_BITS: u32 = 24;
+
+    #[cfg(feature = "status-u128")]
+    const MAX_FIELD_BITS: u32 = 40;
+
+    pub(crate) const FIELD_BITS: u32 = min(
+        (Status::BITS - Self::INDEX_BITS) / BUFS as u32,
+        Self::MAX_FIELD_BITS,
+    );
+
+    const COUNT_BITS: u32 = min(Self::FIELD_BITS - 1, Self::MAX_FIELD_BITS - 8);
+
+    pub(crate) const FIRST_SHIFT: u32 = Status::BITS - BUFS as u32 * Self::FIELD_BITS;
+
+    /// The largest use count that a single slice can hold
+    pub(crate) const MAX_COUNT: Status = (1 << Self::COUNT_BITS) - 1;
+
+    /// A single slice's use count together with its padding
+    pub(crate) const FIELD_MASK: Status = (1 << Self::FIELD_BITS) - 1;
+
+    pub(crate) const INDEX_MASK: Status = (BUFS.next_power_of_two() - 1) as Status;
+
+    /// Set while a writer is parked until the readers of a slice are gone.
+    /// Readers that see this flag when releasing a slice must wake it up.
+    pub(crate) const WRITER_PARKED: Status = 1 << (Self::INDEX_BITS - 1);
+
+    /// Increments the use counts of all slices at once
+    pub(crate) const INC_ALL: Status = {
+        let mut inc = 0;
+        let mut i = 0;
+        while i < BUFS {
+            inc |= 1 << (Self::FIRST_SHIFT + i as u32 * Self::FIELD_BITS);
+            i += 1;
+        }
+        inc
+    };
+
+    pub(crate) const VALID_MASK: Status =
+        (Self::INC_ALL * Self::FIELD_MASK) | Self::WRITER_PARKED | Self::INDEX_MASK;
+
+    /// Increments the use count of the given slice
+    pub(crate) fn inc(slice: u8) -> Status {
+        debug_assert!((slice as usize) < BUFS);
+        1 << (Self::FIRST_SHIFT + slice as u32 * Self::FIELD_BITS)
+    }
+
+    /// The number of readers holding onto the given slice. This includes
+    /// the padding, so that a count which briefly overflowed past `MAX_COUNT`
+    /// still counts every reader.
+    pub(crate) fn use_count(slice: u8, status: Status) -> Status {
+        debug_assert!((slice as usize) < BUFS);
+        (status >> (Self::FIRST_SHIFT + slice as u32 * Self::FIELD_BITS)) & Self::FIELD_MASK
+    }
+
+    /// The index of the slice that new readers are pointed to
+    pub(crate) fn active_slice(status: Status) -> u8 {
+        (status & Self::INDEX_MASK) as u8
+    }
+
+    /// Whether the status word points to a valid slice and has no bits set
+    /// outside of the slices' fields
+    pub(crate) fn valid(status: Status) -> bool {
+        (status & !Self::VALID_MASK) == 0 && (Self::active_slice(status) as usize) < BUFS
+    }
+}
+
+const fn min(a: u32, b: u32) -> u32 {
+    if a < b {
+        a
+    } else {
+        b
+    }
+}
@@ -0,0 +1,231 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::{alloc::Layout, marker::PhantomData, ptr::NonNull};
+
+#[cfg(feature = "allocator-api2")]
+use allocator_api2::alloc::Allocator;
+
+/// The allocation holding all partitions of an `AtomicSlice`. It is only
+/// ever accessed through its raw pointer, so that readers of one partition
+/// and the writer of another never hold overlapping references to the whole
+/// allocation.
+pub(crate) struct Storage<T> {
+    ptr: NonNull<T>,
+    len: usize,
+    owner: Owner,
+    _marker: PhantomData<T>,
+}
+
+/// Where the allocation came from and thus how it is given back
+enum Owner {
+    /// Allocated through a `Box` using the global allocator
+    Global,
+    /// Allocated using the global allocator with a larger alignment than
+    /// that of the elements
+    Aligned(Layout),
+    /// Borrowed for the rest of the program, so that the memory is never
+    /// given back and the elements are never dropped, like any other static
+    Static,
+    /// Allocated using a custom allocator, which is kept alive until the
+    /// allocation is given back to it
+    #[cfg(feature = "allocator-api2")]
+    Allocator(Box<dyn Allocator + Send + Sync>),
+}
+
+impl<T> Storage<T> {
+    /// Take over the allocation of a boxed slice
+    pub(crate) fn new(data: Box<[T]>) -> Storage<T> {
+        let len = data.len();
+        // Safety: the pointer of a box is never null
+        let ptr = unsafe { NonNull::new_unchecked(Box::into_raw(data) as *mut T) };
+        Storage {
+            ptr,
+            len,
+            owner: Owner::Global,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Allocate `len` elements aligned to at least `align` bytes, which must
+    /// be a power of two, and produce each of them by calling `f` with its
+    /// index. If `f` panics, the elements produced so far are leaked. The
+    /// allocation is padded to a multiple of `align` bytes, so that no other
+    /// allocation can share its last `align` bytes.
+    pub(crate) fn new_aligned<F: FnMut(usize) -> T>(
+        len: usize,
+        align: usize,
+        mut f: F,
+    ) -> Storage<T> {
+        let layout = Layout::array::<T>(len)
+            .and_then(|layout| layout.align_to(align))
+            .map(|layout| layout.pad_to_align())
+            .expect("Attempted to create AtomicSlice whose partitions don't fit into memory");
+        let ptr = if layout.size() == 0 {
+            // Nothing needs to be allocated, but the pointer is still aligned
+            NonNull::new(core::ptr::without_provenance_mut(layout.align())).unwrap()
+        } else {
+            // Safety: the layout's size isn't zero
+            let ptr = unsafe { alloc::alloc::alloc(layout) } as *mut T;
+            match NonNull::new(ptr) {
+                Some(ptr) => ptr,
+                None => alloc::alloc::handle_alloc_error(layout),
+            }
+        };
+        for i in 0..len {
+            // Safety: the allocation has room for `len` elements
+            unsafe { ptr.as_ptr().add(i).write(f(i)) };
+        }
+        Storage {
+            ptr,
+            len,
+            owner: Owner::Aligned(layout),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Take over a slice that was borrowed for the rest of the program
+    pub(crate) fn new_static(data: &'static mut [T]) -> Storage<T> {
+        let len = data.len();
+        Storage {
+            ptr: NonNull::from(data).cast(),
+            len,
+            owner: Owner::Static,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Take over the allocation of a vector from a custom allocator,
+    /// shrinking it to fit its elements
+    #[cfg(feature = "allocator-api2")]
+    pub(crate) fn new_in<A: Allocator + Send + Sync + 'static>(
+        data: allocator_api2::vec::Vec<T, A>,
+    ) -> Storage<T> {
+        let len = data.len();
+        let (ptr, allocator) =
+            allocator_api2::boxed::Box::into_raw_with_allocator(data.into_boxed_slice());
+        Storage {
+            // Safety: the pointer of a box is never null
+            ptr: unsafe { NonNull::new_unchecked(ptr as *mut T) },
+            len,
+            owner: Owner::Allocator(Box::new(allocator)),
+            _marker: PhantomData,
+        }
+    }
+
+    /// The total number of elements of all partitions
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// A pointer to the first element, which may be used to read and write
+    /// any element according to the usual aliasing rules
+    pub(crate) fn as_ptr(&self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [T] {
+        // Safety: the elements are initialized and borrowed mutably
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Turn the storage into a vector using the global allocator. This reuses
+    /// the allocation if it came from the global allocator in the first
+    /// place, and moves the elements into a new one otherwise. Elements are
+    /// moved out of static storage too, which is fine since it stays
+    /// borrowed forever and its elements would never have been dropped.
+    pub(crate) fn into_vec(self) -> Vec<T> {
+        let this = core::mem::ManuallyDrop::new(self);
+        // Safety: ownership of the allocation and of the allocator is moved
+        // out of `this`, which is never dropped
+        let owner = unsafe { core::ptr::read(&this.owner) };
+        match owner {
+            Owner::Global => {
+                // Safety: the allocation came from a box of this length
+                let data = unsafe {
+                    Box::from_raw(core::ptr::slice_from_raw_parts_mut(
+                        this.ptr.as_ptr(),
+                        this.len,
+                    ))
+                };
+                Vec::from(data)
+            }
+            Owner::Aligned(layout) => {
+                let mut data = Vec::with_capacity(this.len);
+                // Safety: the elements are moved, after which the allocation
+                // is given back without dropping them again
+                unsafe {
+                    core::ptr::copy_nonoverlapping(this.ptr.as_ptr(), data.as_mut_ptr(), this.len);
+                    data.set_len(this.len);
+                    if layout.size() != 0 {
+                        alloc::alloc::dealloc(this.ptr.as_ptr().cast(), layout);
+                    }
+                }
+                data
+            }
+            Owner::Static => {
+                let mut data = Vec::with_capacity(this.len);
+                // Safety: the elements are moved and never used again
+                unsafe {
+                    core::ptr::copy_nonoverlapping(this.ptr.as_ptr(), data.as_mut_ptr(), this.len);
+                    data.set_len(this.len);
+                }
+                data
+            }
+            #[cfg(feature = "allocator-api2")]
+            Owner::Allocator(allocator) => {
+                let mut data = Vec::with_capacity(this.len);
+                // Safety: the elements are moved, after which the allocation
+                // is given back without dropping them again
+                unsafe {
+                    core::ptr::copy_nonoverlapping(this.ptr.as_ptr(), data.as_mut_ptr(), this.len);
+                    data.set_len(this.len);
+                    deallocate::<T>(&*allocator, this.ptr, this.len);
+                }
+                data
+            }
+        }
+    }
+}
+
+/// Give an allocation of `len` elements back to the allocator it came from
+///
+/// Safety: the allocation must have been made by `allocator` with the layout
+/// of an array of `len` elements, and must not be used afterwards
+#[cfg(feature = "allocator-api2")]
+unsafe fn deallocate<T>(allocator: &dyn Allocator, ptr: NonNull<T>, len: usize) {
+    let layout = Layout::array::<T>(len).unwrap();
+    // Zero-sized allocations are dangling pointers that were never allocated
+    if layout.size() != 0 {
+        allocator.deallocate(ptr.cast(), layout);
+    }
+}
+
+/// An empty allocation, which needs no memory at all
+impl<T> Default for Storage<T> {
+    fn default() -> Self {
+        Storage::new(Box::new([]))
+    }
+}
+
+impl<T> Drop for Storage<T> {
+    fn drop(&mut self) {
+        let data = core::ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), self.len);
+        match &self.owner {
+            // Safety: the allocation came from a box of this length
+            Owner::Global => drop(unsafe { Box::from_raw(data) }),
+            Owner::Aligned(layout) => unsafe {
+                // Safety: the elements are initialized and never used again
+                core::ptr::drop_in_place(data);
+                if layout.size() != 0 {
+                    alloc::alloc::dealloc(self.ptr.as_ptr().cast(), *layout);
+                }
+            },
+            Owner::Static => {}
+            #[cfg(feature = "allocator-api2")]
+            Owner::Allocator(allocator) => unsafe {
+                // Safety: the elements are initialized and never used again
+                core::ptr::drop_in_place(data);
+                deallocate::<T>(&**allocator, self.ptr, self.len);
+            },
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use alloc::vec::Vec;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+
+use crate::AtomicSlice;
+
+/// A `Stream` which yields a snapshot of the contents of an `AtomicSlice`
+/// every time a newer version is published. Created using
+/// `AtomicSlice::subscribe` or `AtomicSlice::subscribe_from`.
+///
+/// Subscriptions only ever yield the latest data. If several versions are
+/// published before the stream is polled again, the intermediate versions
+/// are skipped, since the `AtomicSlice` does not retain them. The stream
+/// never ends.
+pub struct Subscription<'a, T, const BUFS: usize = 2> {
+    atomic_slice: &'a AtomicSlice<T, BUFS>,
+    last_seen: Option<u64>,
+}
+
+impl<T: Clone, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Subscribe to snapshots of the data, beginning with the current contents
+    /// and followed by each newer version as it is published. See
+    /// `Subscription` for details.
+    pub fn subscribe(&self) -> Subscription<'_, T, BUFS> {
+        Subscription {
+            atomic_slice: self,
+            last_seen: None,
+        }
+    }
+
+    /// Subscribe to snapshots of versions newer than `last_seen`. Passing
+    /// the current version yields only the changes made from now on.
+    pub fn subscribe_from(&self, last_seen: u64) -> Subscription<'_, T, BUFS> {
+        Subscription {
+            atomic_slice: self,
+            last_seen: Some(last_seen),
+        }
+    }
+}
+
+impl<'a, T, const BUFS: usize> Subscription<'a, T, BUFS> {
+    /// The version of the most recently yielded snapshot, or the version
+    /// passed to `subscribe_from` if nothing was yielded yet
+    pub fn last_seen(&self) -> Option<u64> {
+        self.last_seen
+    }
+}
+
+impl<'a, T: Clone, const BUFS: usize> Stream for Subscription<'a, T, BUFS> {
+    type Item = Vec<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Vec<T>>> {
+        let this = self.get_mut();
+        let atomic_slice = this.atomic_slice;
+        if let Some(last_seen) = this.last_seen {
+            if atomic_slice
+                .protocol
+                .notifier
+                .poll_until(cx, || atomic_slice.version() > last_seen)
+                .is_pending()
+            {
+                return Poll::Pending;
+            }
+        }
+
+        let guard = atomic_slice.read();
+        this.last_seen = Some(guard.version());
+        Poll::Ready(Some(guard.to_vec()))
+    }
+}
@@ -0,0 +1,119 @@
+//! The atomic types used throughout the crate. With the `portable-atomic`
+//! feature, these come from the `portable-atomic` crate instead of `core`,
+//! which provides them on targets without native 64-bit atomics. With the
+//! `loom` feature or when compiled with `--cfg loom`, they come from `loom`
+//! so that the protocol can be model-checked. With the `shuttle` feature,
+//! they come from `shuttle`, whose atomics and spin loops are yield points
+//! of its scheduler, so that the protocol can be tested under randomized
+//! but reproducible schedules.
+
+#[cfg(all(not(loom), not(shuttle), not(feature = "portable-atomic")))]
+#[allow(unused_imports)]
+pub(crate) use core::sync::atomic::{
+    fence, AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering,
+};
+
+#[cfg(all(not(loom), not(shuttle), feature = "portable-atomic"))]
+#[allow(unused_imports)]
+pub(crate) use portable_atomic::{fence, AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+
+#[cfg(loom)]
+#[allow(unused_imports)]
+pub(crate) use loom::sync::atomic::{fence, AtomicBool, AtomicU32, AtomicU64, Ordering};
+
+// Waiting for updates is not modelled, and the notifier has to stay constructible
+// in constant contexts
+#[cfg(loom)]
+pub(crate) use core::sync::atomic::AtomicUsize;
+
+#[cfg(shuttle)]
+#[allow(unused_imports)]
+pub(crate) use shuttle::sync::atomic::{
+    fence, AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering,
+};
+
+#[cfg(not(any(loom, shuttle)))]
+pub(crate) use core::hint::spin_loop;
+
+#[cfg(loom)]
+pub(crate) use loom::hint::spin_loop;
+
+#[cfg(shuttle)]
+pub(crate) use shuttle::hint::spin_loop;
+
+#[cfg(all(not(any(loom, shuttle)), feature = "std"))]
+pub(crate) use std::thread::yield_now;
+
+#[cfg(all(loom, feature = "std"))]
+pub(crate) use loom::thread::yield_now;
+
+#[cfg(shuttle)]
+pub(crate) use shuttle::thread::yield_now;
+
+/// Whether the target only ever runs a single thread, as is the case for
+/// WebAssembly without the `atomics` target feature. Waiting for another
+/// thread to release a read lock, finish writing or publish an update would
+/// then wait forever, and so `wait_forever` panics instead.
+pub(crate) const SINGLE_THREADED: bool =
+    cfg!(all(target_family = "wasm", not(target_feature = "atomics")));
+
+/// Panic because the current thread is about to wait for another thread on
+/// a target without any other threads, see `SINGLE_THREADED`
+#[cold]
+#[inline(never)]
+pub(crate) fn wait_forever() -> ! {
+    panic!(
+        "AtomicSlice would wait forever for another thread on a target \
+        which only runs a single thread"
+    );
+}
+
+/// The atomic counterpart of `status::Status`
+#[cfg(not(any(feature = "status-u32", feature = "status-u128")))]
+pub(crate) type AtomicStatus = AtomicU64;
+
+/// The atomic counterpart of `status::Status`
+#[cfg(feature = "status-u32")]
+pub(crate) type AtomicStatus = AtomicU32;
+
+/// The atomic counterpart of `status::Status`. There is no stable native
+/// 128-bit atomic type, so this always comes from `portable-atomic`, which
+/// uses native instructions where they are available.
+#[cfg(feature = "status-u128")]
+pub(crate) type AtomicStatus = portable_atomic::AtomicU128;
+
+/// Pads and aligns a value to the size of a cache line, so that frequent
+/// atomic operations on it don't cause false sharing with its neighbours.
+/// Like in `crossbeam-utils`, 128 bytes are used on those architectures
+/// whose prefetchers pull in pairs of 64-byte cache lines.
+#[cfg_attr(
+    any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "powerpc64"
+    ),
+    repr(align(128))
+)]
+#[cfg_attr(
+    not(any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "powerpc64"
+    )),
+    repr(align(64))
+)]
+pub(crate) struct CachePadded<T>(T);
+
+impl<T> CachePadded<T> {
+    pub(crate) const fn new(value: T) -> CachePadded<T> {
+        CachePadded(value)
+    }
+}
+
+impl<T> core::ops::Deref for CachePadded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
@@ -1,9 +1,25 @@
 use std::sync::{
-    atomic::{AtomicU8, Ordering},
+    atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering},
     Arc,
 };
 
-use crate::AtomicSlice;
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+use crate::{
+    status::Layout, AtomicArcSlice, AtomicArray, AtomicSlice, AtomicSlice2D, AtomicSliceBank,
+    AtomicSliceGroup, AtomicSliceInline, AtomicValue, AtomicVec, Backoff, LengthMismatch,
+    ShardedAtomicSlice, TryWriteError, VersionMismatch,
+};
+
+#[cfg(not(any(feature = "status-u32", feature = "status-u128")))]
+use crate::constants;
+
+#[cfg(feature = "std")]
+use crate::WriteTimeout;
+
+#[cfg(feature = "metrics")]
+use crate::WriteStats;
 
 trait TestType:
     Default
@@ -13,6 +29,7 @@ trait TestType:
     + std::fmt::Display
     + std::cmp::PartialEq
     + Send
+    + Sync
     + 'static
 {
 }
@@ -24,12 +41,12 @@ struct TestConfig {
     num_iterations: usize,
 }
 
-fn single_test_helper<T: TestType>(config: TestConfig) {
+fn single_test_helper<T: TestType, const BUFS: usize>(config: TestConfig) {
     let next_value_to_write = Arc::new(AtomicU8::new(0));
 
     let mut data = Vec::<T>::new();
     data.resize(config.length, T::default());
-    let atomic_slice = Arc::new(AtomicSlice::new(data));
+    let atomic_slice = Arc::new(AtomicSlice::<T, BUFS>::new_with_buffers(data));
 
     let readers: Vec<std::thread::JoinHandle<()>> = (0..config.num_readers)
         .map(|i_reader| {
@@ -38,10 +55,10 @@ fn single_test_helper<T: TestType>(config: TestConfig) {
                 for iter in 0..config.num_iterations {
                     // Read the slice and assert that its length is as expected and that all values are the same
                     let guard = atomic_slice.read();
-                    let slice: &[T] = &*guard;
+                    let slice: &[T] = &guard;
                     assert_eq!(slice.len(), config.length);
                     let first_value = slice[0];
-                    for other_value in slice[1..].iter().cloned() {
+                    for &other_value in &slice[1..] {
                         assert_eq!(
                             first_value, other_value,
                             "Reader {} encountered a slice with mis-matched values {} != {} on iteration {}: {:?}",
@@ -78,13 +95,22 @@ fn single_test_helper<T: TestType>(config: TestConfig) {
     for t in writers {
         t.join().unwrap();
     }
+
+    // Every read lock has been released again, including those which were
+    // briefly taken on a slice that turned out not to be active anymore
+    let status = unsafe { (*atomic_slice.raw_status()).load(Ordering::SeqCst) };
+    for i in 0..BUFS as u8 {
+        assert_eq!(Layout::<BUFS>::use_count(i, status), 0);
+    }
 }
 
-fn test_grid_helper<T: TestType>() {
+fn test_grid_helper<T: TestType, const BUFS: usize>() {
+    // Smaller status words can't hold as many simultaneous reads
+    let max_readers = (Layout::<BUFS>::MAX_COUNT as usize).min(4);
     for length_bits in 0..=8 {
-        for num_readers in 1..=4 {
+        for num_readers in 1..=max_readers {
             for num_writers in 1..=4 {
-                single_test_helper::<T>(TestConfig {
+                single_test_helper::<T, BUFS>(TestConfig {
                     length: (1 << length_bits),
                     num_readers,
                     num_writers,
@@ -104,32 +130,32 @@ impl TestType for f64 {}
 
 #[test]
 fn test_atomic_slice_u8() {
-    test_grid_helper::<u8>()
+    test_grid_helper::<u8, 2>()
 }
 
 #[test]
 fn test_atomic_slice_u16() {
-    test_grid_helper::<u16>()
+    test_grid_helper::<u16, 2>()
 }
 
 #[test]
 fn test_atomic_slice_u32() {
-    test_grid_helper::<u32>()
+    test_grid_helper::<u32, 2>()
 }
 
 #[test]
 fn test_atomic_slice_u64() {
-    test_grid_helper::<u64>()
+    test_grid_helper::<u64, 2>()
 }
 
 #[test]
 fn test_atomic_slice_f32() {
-    test_grid_helper::<f32>()
+    test_grid_helper::<f32, 2>()
 }
 
 #[test]
 fn test_atomic_slice_f64() {
-    test_grid_helper::<f64>()
+    test_grid_helper::<f64, 2>()
 }
 
 #[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
@@ -144,7 +170,7 @@ impl From<u8> for ExampleStruct {
         ExampleStruct {
             x: value.into(),
             y: value.into(),
-            z: value.into(),
+            z: value,
         }
     }
 }
@@ -159,8 +185,2682 @@ impl TestType for ExampleStruct {}
 
 #[test]
 fn test_atomic_slice_example_struct() {
-    test_grid_helper::<ExampleStruct>();
+    test_grid_helper::<ExampleStruct, 2>();
+}
+
+#[test]
+fn test_atomic_slice_3_buffers() {
+    test_grid_helper::<u32, 3>();
+}
+
+#[test]
+fn test_atomic_slice_4_buffers() {
+    test_grid_helper::<u32, 4>();
+}
+
+#[test]
+fn test_atomic_slice_8_buffers() {
+    test_grid_helper::<u32, 8>();
 }
 
 // TODO: add a test for multiple overlapping reads on the same thread.
 // should work just fine but better to test anyway.
+
+#[test]
+fn test_write_with() {
+    let atomic_slice = AtomicSlice::new(vec![0_u32; 16]);
+    for value in 1..=4 {
+        atomic_slice.write_with(|slice| {
+            assert_eq!(slice.len(), 16);
+            for (i, v) in slice.iter_mut().enumerate() {
+                *v = value * 100 + i as u32;
+            }
+        });
+        let guard = atomic_slice.read();
+        for (i, v) in guard.iter().enumerate() {
+            assert_eq!(*v, value * 100 + i as u32);
+        }
+    }
+}
+
+#[test]
+fn test_try_write() {
+    let atomic_slice = AtomicSlice::new(vec![0_u8; 4]);
+    assert_eq!(atomic_slice.try_write(&[1; 4]), Ok(()));
+
+    // Hold onto the current data across a write so that it becomes the inactive slice
+    let guard = atomic_slice.read();
+    assert_eq!(*guard, [1; 4]);
+    assert_eq!(atomic_slice.try_write(&[2; 4]), Ok(()));
+    assert_eq!(
+        atomic_slice.try_write(&[3; 4]),
+        Err(TryWriteError::ReadInProgress)
+    );
+    assert_eq!(*guard, [1; 4]);
+    drop(guard);
+
+    atomic_slice.write_with(|_| {
+        assert_eq!(
+            atomic_slice.try_write(&[4; 4]),
+            Err(TryWriteError::WriteInProgress)
+        );
+    });
+
+    assert_eq!(atomic_slice.try_write(&[5; 4]), Ok(()));
+    assert_eq!(*atomic_slice.read(), [5; 4]);
+}
+
+#[test]
+fn test_write_checked() {
+    let atomic_slice = AtomicSlice::new(vec![0_u16; 8]);
+    assert_eq!(atomic_slice.write_checked(&[1; 8]), Ok(()));
+    assert_eq!(
+        atomic_slice.write_checked(&[2; 7]),
+        Err(LengthMismatch {
+            expected: 8,
+            actual: 7
+        })
+    );
+    assert_eq!(*atomic_slice.read(), [1; 8]);
+    assert_eq!(atomic_slice.version(), 1);
+
+    let write = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        atomic_slice.write(&[]);
+    }));
+    assert!(write.is_err());
+}
+
+#[test]
+fn test_write_clones_each_element() {
+    static CLONES: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug, PartialEq)]
+    struct Counted(String);
+
+    impl Clone for Counted {
+        fn clone(&self) -> Counted {
+            CLONES.fetch_add(1, Ordering::Relaxed);
+            Counted(self.0.clone())
+        }
+    }
+
+    let atomic_slice = AtomicSlice::new(vec![Counted(String::new()); 3]);
+    CLONES.store(0, Ordering::Relaxed);
+
+    let data = [
+        Counted("a".to_string()),
+        Counted("b".to_string()),
+        Counted("c".to_string()),
+    ];
+    atomic_slice.write(&data);
+    assert_eq!(CLONES.load(Ordering::Relaxed), 3);
+    assert_eq!(*atomic_slice.read(), data);
+
+    let data = [
+        Counted("d".to_string()),
+        Counted("e".to_string()),
+        Counted("f".to_string()),
+    ];
+    assert_eq!(atomic_slice.try_write(&data), Ok(()));
+    assert_eq!(CLONES.load(Ordering::Relaxed), 6);
+
+    // The written data is owned by the AtomicSlice and not shared with the caller
+    let guard = atomic_slice.read();
+    assert_eq!(*guard, data);
+    assert_ne!(guard[0].0.as_ptr(), data[0].0.as_ptr());
+}
+
+#[test]
+fn test_update() {
+    let atomic_slice = Arc::new(AtomicSlice::new(vec![0_u64; 4]));
+
+    // Concurrent increments must not get lost
+    let writers: Vec<std::thread::JoinHandle<()>> = (0..4)
+        .map(|_| {
+            let atomic_slice = Arc::clone(&atomic_slice);
+            std::thread::spawn(move || {
+                for _ in 0..1000 {
+                    atomic_slice.update(|previous, slice| {
+                        for (v, p) in slice.iter_mut().zip(previous) {
+                            *v = p + 1;
+                        }
+                    });
+                }
+            })
+        })
+        .collect();
+    for t in writers {
+        t.join().unwrap();
+    }
+
+    assert_eq!(*atomic_slice.read(), [4000; 4]);
+    assert_eq!(atomic_slice.version(), 4000);
+}
+
+#[test]
+fn test_compare_and_write() {
+    let atomic_slice = AtomicSlice::new(vec![0_u8; 4]);
+
+    let guard = atomic_slice.read();
+    assert_eq!(
+        atomic_slice.compare_and_write(guard.version(), &[1; 4]),
+        Ok(1)
+    );
+    assert_eq!(
+        atomic_slice.compare_and_write(guard.version(), &[2; 4]),
+        Err(VersionMismatch {
+            expected: 0,
+            actual: 1
+        })
+    );
+    drop(guard);
+
+    assert_eq!(*atomic_slice.read(), [1; 4]);
+    assert_eq!(atomic_slice.compare_and_write(1, &[3; 4]), Ok(2));
+    assert_eq!(*atomic_slice.read(), [3; 4]);
+}
+
+#[test]
+fn test_fetch_update() {
+    let atomic_slice = Arc::new(AtomicSlice::new(vec![0_u64; 4]));
+
+    let writers: Vec<std::thread::JoinHandle<()>> = (0..4)
+        .map(|_| {
+            let atomic_slice = Arc::clone(&atomic_slice);
+            std::thread::spawn(move || {
+                for _ in 0..1000 {
+                    let result = atomic_slice
+                        .fetch_update(|previous| Some(previous.iter().map(|v| v + 1).collect()));
+                    assert!(result.is_ok());
+                }
+            })
+        })
+        .collect();
+    for t in writers {
+        t.join().unwrap();
+    }
+
+    assert_eq!(*atomic_slice.read(), [4000; 4]);
+    assert_eq!(atomic_slice.fetch_update(|_| None), Err(4000));
+    assert_eq!(
+        atomic_slice.fetch_update(|previous| Some(previous.to_vec())),
+        Ok(4001)
+    );
+}
+
+#[test]
+fn test_write_if_changed() {
+    let atomic_slice = AtomicSlice::new(vec![0_u8; 4]);
+    assert!(!atomic_slice.write_if_changed(&[0; 4]));
+    assert_eq!(atomic_slice.version(), 0);
+    assert!(atomic_slice.write_if_changed(&[0, 0, 1, 0]));
+    assert!(!atomic_slice.write_if_changed(&[0, 0, 1, 0]));
+    assert_eq!(atomic_slice.version(), 1);
+    assert_eq!(*atomic_slice.read(), [0, 0, 1, 0]);
+}
+
+#[test]
+fn test_write_iter() {
+    let atomic_slice = AtomicSlice::new(vec![0_u32; 4]);
+    atomic_slice.write_iter((1..5).map(|i| i * i));
+    assert_eq!(*atomic_slice.read(), [1, 4, 9, 16]);
+
+    let write = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        atomic_slice.write_iter(0..3);
+    }));
+    assert!(write.is_err());
+    assert_eq!(*atomic_slice.read(), [1, 4, 9, 16]);
+    assert_eq!(atomic_slice.version(), 1);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_byte_writer() {
+    use std::io::Write;
+
+    let atomic_slice = AtomicSlice::new(vec![0_u8; 8]);
+    let mut writer = atomic_slice.writer();
+    writer.write_all(b"abc").unwrap();
+    writer.write_all(b"def").unwrap();
+    assert_eq!(*atomic_slice.read(), [0; 8]);
+    writer.flush().unwrap();
+    assert_eq!(*atomic_slice.read(), *b"abcdef\0\0");
+    assert_eq!(atomic_slice.version(), 1);
+
+    write!(writer, "{}", 12345678).unwrap();
+    assert!(writer.write_all(b"9").is_err());
+    writer.flush().unwrap();
+    assert_eq!(*atomic_slice.read(), *b"12345678");
+    assert_eq!(atomic_slice.version(), 2);
+
+    writer.write_all(b"x").unwrap();
+    drop(writer);
+    assert_eq!(*atomic_slice.read(), *b"12345678");
+    assert_eq!(atomic_slice.try_write(b"abcdefgh"), Ok(()));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_byte_reader() {
+    use std::io::{BufRead, Read};
+
+    let atomic_slice = AtomicSlice::new(b"first\nsecond".to_vec());
+    let mut reader = atomic_slice.reader();
+    atomic_slice.write(b"third\nfourth");
+
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    assert_eq!(line, "first\n");
+    assert_eq!(reader.position(), 6);
+
+    let mut rest = Vec::new();
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"second");
+    assert_eq!(reader.version(), 0);
+    assert_eq!(reader.read(&mut [0; 4]).unwrap(), 0);
+
+    let mut reader = atomic_slice.reader();
+    let mut buf = [0; 5];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"third");
+    assert_eq!(reader.version(), 1);
+    assert_eq!(*reader.into_inner(), *b"third\nfourth");
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn test_bytemuck() {
+    let atomic_slice = AtomicSlice::new(vec![0x0102_u16, 0x0304]);
+    assert_eq!(
+        atomic_slice.read().as_bytes(),
+        [0x0102_u16.to_ne_bytes(), 0x0304_u16.to_ne_bytes()].concat()
+    );
+
+    // Deliberately misaligned
+    let bytes = [0_u8, 5, 6, 7, 8];
+    let data = [u16::from_ne_bytes([5, 6]), u16::from_ne_bytes([7, 8])];
+    assert_eq!(atomic_slice.write_bytes(&bytes[1..]), Ok(()));
+    assert_eq!(*atomic_slice.read(), data);
+
+    assert_eq!(
+        atomic_slice.write_bytes(&bytes),
+        Err(LengthMismatch {
+            expected: 4,
+            actual: 5
+        })
+    );
+    assert_eq!(atomic_slice.version(), 1);
+}
+
+#[cfg(feature = "zerocopy")]
+#[test]
+fn test_zerocopy() {
+    let bytes = [0_u8, 1, 2, 3, 4, 5, 6, 7, 8];
+    assert!(AtomicSlice::<u32>::from_bytes(&bytes).is_none());
+    let atomic_slice = AtomicSlice::<u32>::from_bytes(&bytes[1..]).unwrap();
+    assert_eq!(
+        *atomic_slice.read(),
+        [
+            u32::from_ne_bytes([1, 2, 3, 4]),
+            u32::from_ne_bytes([5, 6, 7, 8])
+        ]
+    );
+    assert_eq!(
+        zerocopy::IntoBytes::as_bytes(&*atomic_slice.read()),
+        &bytes[1..]
+    );
+
+    assert_eq!(atomic_slice.write_from_bytes(&[9; 8]), Ok(()));
+    assert_eq!(*atomic_slice.read(), [u32::from_ne_bytes([9; 4]); 2]);
+    assert_eq!(
+        atomic_slice.write_from_bytes(&[9; 4]),
+        Err(LengthMismatch {
+            expected: 8,
+            actual: 4
+        })
+    );
+
+    let guard = atomic_slice.read();
+    assert_eq!(guard.read_as::<u16>(), Some(&[0x0909_u16; 4][..]));
+    assert_eq!(guard.read_as::<[u8; 8]>(), Some(&[[9_u8; 8]][..]));
+    assert_eq!(guard.read_as::<[u8; 3]>(), None);
+    assert_eq!(guard.read_as::<u128>(), None);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    use serde::{de::value::SeqDeserializer, Deserialize};
+    use serde_test::{assert_ser_tokens, Token};
+
+    let atomic_slice = AtomicSlice::new(vec![1_u8, 2]);
+    let _guard = atomic_slice.read();
+    atomic_slice.write(&[3, 4]);
+    let tokens = [
+        Token::Seq { len: Some(2) },
+        Token::U8(3),
+        Token::U8(4),
+        Token::SeqEnd,
+    ];
+    assert_ser_tokens(&atomic_slice, &tokens);
+
+    let deserializer = SeqDeserializer::<_, serde::de::value::Error>::new([3_u8, 4].into_iter());
+    let atomic_slice = AtomicSlice::<u8, 3>::deserialize(deserializer).unwrap();
+    assert_eq!(*atomic_slice.read(), [3, 4]);
+    assert_eq!(atomic_slice.version(), 0);
+}
+
+#[test]
+fn test_debug() {
+    let atomic_slice = AtomicSlice::new(vec![1_u8, 2, 3]);
+    atomic_slice.write(&[4, 5, 6]);
+    assert_eq!(
+        format!("{atomic_slice:?}"),
+        "AtomicSlice { len: 3, version: 1, data: [4, 5, 6] }"
+    );
+    assert_eq!(
+        format!("{:?}", atomic_slice.read()),
+        "AtomicSliceReadGuard { version: 1, data: [4, 5, 6] }"
+    );
+
+    let atomic_slice = AtomicSlice::new((0..100).collect());
+    let expected = format!(
+        "AtomicSlice {{ len: 100, version: 0, data: {} }}",
+        format!("{:?}", (0..32).collect::<Vec<_>>()).replace(']', ", ..]")
+    );
+    assert_eq!(format!("{atomic_slice:?}"), expected);
+}
+
+#[test]
+fn test_clone() {
+    let mut atomic_slice = AtomicSlice::<u8, 3>::new_with_buffers(vec![1, 2]);
+    atomic_slice.set_backoff(Backoff::Exponential);
+    atomic_slice.write(&[3, 4]);
+    let _guard = atomic_slice.read();
+
+    let clone = atomic_slice.clone();
+    assert_eq!(*clone.read(), [3, 4]);
+    assert_eq!(clone.version(), 0);
+    assert_eq!(clone.protocol.backoff(), Backoff::Exponential);
+
+    clone.write(&[5, 6]);
+    assert_eq!(*atomic_slice.read(), [3, 4]);
+    assert_eq!(*clone.read(), [5, 6]);
+}
+
+#[test]
+fn test_conversions() {
+    let from_vec: AtomicSlice<u8> = vec![1, 2].into();
+    let from_slice: AtomicSlice<u8, 3> = [1, 2][..].into();
+    let from_array: AtomicSlice<u8> = [1, 2].into();
+    let from_iter: AtomicSlice<u8, 4> = (1..3).collect();
+    assert_eq!(*from_vec.read(), [1, 2]);
+    assert_eq!(*from_slice.read(), [1, 2]);
+    assert_eq!(*from_array.read(), [1, 2]);
+    assert_eq!(*from_iter.read(), [1, 2]);
+
+    let default = AtomicSlice::<String, 3>::default();
+    assert!(default.is_empty());
+    default.write(&[]);
+    assert!(default.read().is_empty());
+    assert_eq!(default.version(), 1);
+}
+
+#[test]
+fn test_eq_and_hash() {
+    use std::hash::BuildHasher;
+
+    let a = AtomicSlice::new(vec![1_u8, 2]);
+    let b = AtomicSlice::new(vec![3_u8, 4]);
+    assert_eq!(a, a);
+    assert_ne!(a, b);
+
+    b.write(&[1, 2]);
+    assert_eq!(a, b);
+
+    // Like slices, contents that don't equal themselves aren't equal
+    let nan = AtomicSlice::new(vec![f32::NAN]);
+    assert_ne!(nan, nan);
+    assert_eq!(AtomicSlice::new(vec![0.5_f32]), AtomicSlice::new(vec![0.5]));
+
+    let state = std::hash::RandomState::new();
+    assert_eq!(state.hash_one(&a), state.hash_one(&b));
+    assert_eq!(state.hash_one(&a), state.hash_one([1_u8, 2].as_slice()));
+}
+
+#[test]
+fn test_get() {
+    let atomic_slice = AtomicSlice::new(vec![1_u8, 2]);
+    assert_eq!(atomic_slice.get(1), Some(2));
+    assert_eq!(atomic_slice.get(2), None);
+
+    // The read lock is released again
+    atomic_slice.write(&[3, 4]);
+    assert_eq!(atomic_slice.try_write(&[5, 6]), Ok(()));
+    assert_eq!(atomic_slice.get(0), Some(5));
+}
+
+#[test]
+fn test_subscribe_range() {
+    let atomic_slice = AtomicSlice::new(vec![0_u8; 4]);
+    let mut subscription = atomic_slice.subscribe_range(1..3);
+    assert!(!subscription.has_changed());
+
+    atomic_slice.write(&[1, 0, 0, 1]);
+    assert!(!subscription.has_changed());
+
+    atomic_slice.write(&[1, 2, 0, 1]);
+    assert!(subscription.has_changed());
+    assert!(subscription.has_changed());
+    assert_eq!(*subscription.read(), [2, 0]);
+    assert!(!subscription.has_changed());
+
+    // Changing the range back counts as unchanged
+    atomic_slice.write(&[1, 3, 0, 1]);
+    atomic_slice.write(&[1, 2, 0, 1]);
+    assert!(!subscription.has_changed());
+
+    #[cfg(feature = "std")]
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            subscription.wait();
+            assert_eq!(*subscription.read(), [2, 5]);
+        });
+        for i in 0..4 {
+            atomic_slice.write(&[i, 2, 0, i]);
+        }
+        atomic_slice.write(&[0, 2, 5, 0]);
+    });
+}
+
+#[test]
+fn test_into_vec() {
+    let atomic_slice = AtomicSlice::<String, 3>::new_with_buffers(vec!["a".into(), "b".into()]);
+    assert_eq!(atomic_slice.into_vec(), ["a", "b"]);
+
+    let atomic_slice = AtomicSlice::<String, 3>::new_with_buffers(vec!["a".into(), "b".into()]);
+    atomic_slice.write(&["c".into(), "d".into()]);
+    atomic_slice.write(&["e".into(), "f".into()]);
+    let data = atomic_slice.into_vec();
+    assert_eq!(data, ["e", "f"]);
+    assert!(data.capacity() >= 6);
+}
+
+#[test]
+fn test_get_mut() {
+    let mut atomic_slice = AtomicSlice::<u8, 3>::new_with_buffers(vec![1, 2]);
+    atomic_slice.write(&[3, 4]);
+    atomic_slice.get_mut()[1] = 5;
+    assert_eq!(*atomic_slice.read(), [3, 5]);
+    assert_eq!(atomic_slice.version(), 1);
+
+    atomic_slice.update(|previous, next| {
+        assert_eq!(previous, [3, 5]);
+        next.copy_from_slice(previous);
+    });
+    assert_eq!(*atomic_slice.read(), [3, 5]);
+}
+
+#[test]
+fn test_fill() {
+    let atomic_slice = AtomicSlice::new(vec![1_u8, 2, 3]);
+    atomic_slice.fill(7);
+    assert_eq!(*atomic_slice.read(), [7, 7, 7]);
+    assert_eq!(atomic_slice.version(), 1);
+}
+
+#[test]
+fn test_atomic_value() {
+    let value = AtomicValue::new(String::from("a"));
+    let guard = value.read();
+    value.write("b".into());
+    assert_eq!(*guard, "a");
+    assert_eq!(*value.read(), "b");
+    assert_eq!(value.read().version(), 1);
+
+    assert_eq!(
+        value.try_write("c".into()),
+        Err(("c".into(), TryWriteError::ReadInProgress))
+    );
+    drop(guard);
+    assert_eq!(value.try_write("c".into()), Ok(()));
+
+    value.write_with(|v| v.push('d'));
+    assert_eq!(*value.read(), "bd");
+    assert_eq!(value.version(), 3);
+}
+
+#[test]
+fn test_atomic_vec() {
+    let atomic_vec = AtomicVec::<u8, 3>::new_with_buffers(vec![1, 2, 3]);
+    let guard = atomic_vec.read();
+    atomic_vec.write(&[4, 5]);
+    atomic_vec.write_vec(vec![6; 10]);
+    assert_eq!(*guard, [1, 2, 3]);
+    assert_eq!(*atomic_vec.read(), [6; 10]);
+
+    drop(guard);
+    atomic_vec.write_with(|vec| vec.push(7));
+    assert_eq!(*atomic_vec.read(), [1, 2, 3, 7]);
+    assert_eq!(atomic_vec.version(), 3);
+
+    atomic_vec.write(&[]);
+    assert!(atomic_vec.read().is_empty());
+}
+
+#[test]
+fn test_atomic_slice_2d() {
+    let matrix = AtomicSlice2D::new(3, 2, vec![0_u8; 6]);
+    assert_eq!(matrix.shape(), (3, 2));
+
+    matrix.write(&[1, 2, 3, 4, 5, 6]);
+    matrix.write_row(2, &[7, 8]);
+    matrix.write_rows([(0, &[9, 9][..]), (1, &[0, 0][..])]);
+
+    let guard = matrix.read();
+    assert_eq!(guard.shape(), (3, 2));
+    assert_eq!(guard.row(0), [9, 9]);
+    assert_eq!(guard.row(2), [7, 8]);
+    assert_eq!(*guard, [9, 9, 0, 0, 7, 8]);
+    assert_eq!(guard.iter_rows().count(), 3);
+    assert_eq!(guard.version(), 3);
+    drop(guard);
+
+    let write = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        matrix.write_rows([(0, &[1, 1][..]), (3, &[1, 1][..])]);
+    }));
+    assert!(write.is_err());
+    assert_eq!(matrix.version(), 3);
+    #[cfg(feature = "std")]
+    assert!(!matrix.as_flat().is_poisoned());
+
+    let empty = AtomicSlice2D::<u8>::new(4, 0, Vec::new());
+    assert_eq!(empty.read().iter_rows().count(), 4);
+}
+
+#[test]
+fn test_atomic_slice_bank() {
+    let bank = AtomicSliceBank::new(3, vec![0_u8; 2]);
+    assert_eq!((bank.channels(), bank.channel_len()), (3, 2));
+    assert_eq!(*bank.read(), [0; 6]);
+
+    bank.write_channel(1, &[1, 2]);
+    assert_eq!(*bank.read_channel(1), [1, 2]);
+    assert_eq!(*bank.read(), [0, 0, 1, 2, 0, 0]);
+
+    bank.write_channels(&[&[3, 3], &[4, 4], &[5, 5]]);
+    let guard = bank.read();
+    assert_eq!(guard.channel(2), [5, 5]);
+    assert_eq!(
+        guard.iter_channels().collect::<Vec<_>>(),
+        [[3, 3], [4, 4], [5, 5]]
+    );
+    assert_eq!(guard.version(), 2);
+    drop(guard);
+
+    bank.write_with(|channel, slice| slice.fill(channel as u8));
+    assert_eq!(*bank.read(), [0, 0, 1, 1, 2, 2]);
+}
+
+#[test]
+fn test_atomic_slice_group() {
+    let group = AtomicSliceGroup::<_, 3>::new_with_buffers((vec![0.0_f32; 2], vec![String::new()]));
+    let guard = group.read();
+    group.write((&[1.0, 2.0], &["a".into()]));
+    group.write_with(|(coefficients, names)| {
+        coefficients.fill(3.0);
+        names[0] = "b".into();
+    });
+    assert_eq!(guard.0, [0.0; 2]);
+    assert_eq!(guard.1, [""]);
+    drop(guard);
+
+    let guard = group.read();
+    assert_eq!(*guard, (vec![3.0; 2], vec!["b".into()]));
+    assert_eq!(guard.version(), 2);
+    drop(guard);
+
+    let write = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        group.write((&[1.0], &["c".into()]));
+    }));
+    assert!(write.is_err());
+    assert_eq!(group.version(), 2);
+
+    // Nothing was locked yet, so nothing is poisoned
+    #[cfg(feature = "std")]
+    assert!(!group.is_poisoned());
+    group.write((&[4.0, 5.0], &["c".into()]));
+    assert_eq!(*group.read(), (vec![4.0, 5.0], vec!["c".into()]));
+}
+
+#[test]
+fn test_read_with_previous() {
+    let atomic_slice = AtomicSlice::new(vec![0_u8; 2]);
+    let pair = atomic_slice.read_with_previous();
+    assert_eq!(**pair.current(), [0, 0]);
+    assert_eq!(**pair.previous(), [0, 0]);
+    assert_eq!(
+        atomic_slice.try_write(&[1, 1]),
+        Err(TryWriteError::ReadInProgress)
+    );
+    drop(pair);
+
+    atomic_slice.write(&[1, 1]);
+    atomic_slice.write(&[2, 2]);
+    let pair = atomic_slice.read_with_previous();
+    assert_eq!(**pair.current(), [2, 2]);
+    assert_eq!(**pair.previous(), [1, 1]);
+    assert_eq!(pair.current().version(), 2);
+    assert_eq!(pair.previous().version(), 1);
+
+    let (current, previous) = pair.into_parts();
+    assert_eq!(*previous, [1, 1]);
+    drop(previous);
+    assert_eq!(atomic_slice.try_write(&[3, 3]), Ok(()));
+    assert_eq!(*current, [2, 2]);
+
+    let atomic_slice = AtomicSlice::<u8, 3>::new_with_buffers(vec![0; 2]);
+    atomic_slice.write(&[1, 1]);
+    let pair = atomic_slice.read_with_previous();
+    atomic_slice.write(&[2, 2]);
+    assert_eq!(**pair.previous(), [0, 0]);
+    assert_eq!(**pair.current(), [1, 1]);
+    drop(pair);
+
+    // The other partitions of a buffer are never read from before they are
+    // written to
+    let atomic_slice = AtomicSlice::<u8>::from_boxed_buffer(vec![1, 1, 9, 9].into_boxed_slice());
+    let pair = atomic_slice.read_with_previous();
+    assert_eq!(**pair.previous(), [1, 1]);
+    assert_eq!(pair.previous().version(), 0);
+    drop(pair);
+    atomic_slice.write(&[2, 2]);
+    let pair = atomic_slice.read_with_previous();
+    assert_eq!(**pair.previous(), [1, 1]);
+    assert_eq!(**pair.current(), [2, 2]);
+}
+
+#[test]
+fn test_read_back() {
+    let atomic_slice = AtomicSlice::<u8, 4>::new_with_buffers(vec![0]);
+    assert_eq!(*atomic_slice.read_back(0).unwrap(), [0]);
+    assert!(atomic_slice.read_back(1).is_none());
+
+    for i in 1..=5 {
+        atomic_slice.write(&[i]);
+    }
+    for n in 0..4 {
+        let guard = atomic_slice.read_back(n).unwrap();
+        assert_eq!(*guard, [5 - n as u8]);
+        assert_eq!(guard.version(), 5 - n);
+    }
+    assert!(atomic_slice.read_back(4).is_none());
+
+    // Holding onto old data shifts the history
+    let oldest = atomic_slice.read_back(3).unwrap();
+    atomic_slice.write(&[6]);
+    assert_eq!(*atomic_slice.read_back(1).unwrap(), [5]);
+    assert!(atomic_slice.read_back(3).is_none());
+    assert_eq!(*oldest, [2]);
+    drop(oldest);
+
+    // The other partitions of a buffer only count once they are written to
+    let buffer: &'static mut [u8] = Box::leak(Box::new([1, 9, 9]));
+    let atomic_slice = AtomicSlice::<u8, 3>::new_in_buffer(buffer);
+    assert_eq!(*atomic_slice.read_back(0).unwrap(), [1]);
+    assert!(atomic_slice.read_back(1).is_none());
+    atomic_slice.write(&[2]);
+    assert_eq!(*atomic_slice.read_back(1).unwrap(), [1]);
+    assert!(atomic_slice.read_back(2).is_none());
+    atomic_slice.write(&[3]);
+    assert_eq!(*atomic_slice.read_back(2).unwrap(), [1]);
+}
+
+#[test]
+fn test_atomic_arc_slice() {
+    let data: Arc<[u8]> = Arc::from(vec![1, 2]);
+    let atomic_slice = AtomicArcSlice::new(Arc::clone(&data));
+    assert!(Arc::ptr_eq(&atomic_slice.load(), &data));
+
+    let guard = atomic_slice.read();
+    let new_data: Arc<[u8]> = Arc::from(vec![3, 4, 5]);
+    atomic_slice.write(Arc::clone(&new_data));
+    assert_eq!(*guard, [1, 2]);
+    drop(guard);
+
+    // Loaded pointers don't hold up writers
+    let loaded = atomic_slice.load();
+    assert!(Arc::ptr_eq(&loaded, &new_data));
+    atomic_slice.write_vec(vec![6]);
+    atomic_slice.write_vec(vec![7]);
+    assert_eq!(*atomic_slice.read(), [7]);
+    assert_eq!(atomic_slice.version(), 3);
+    assert_eq!(*loaded, [3, 4, 5]);
+
+    // Replaced slices are dropped
+    drop(atomic_slice);
+    assert_eq!(Arc::strong_count(&data), 1);
+}
+
+#[cfg(feature = "capi")]
+#[test]
+fn test_capi() {
+    use crate::capi::*;
+
+    unsafe {
+        let data = [1.0, 2.0, 3.0];
+        let atomic_slice = atomicslice_new(data.as_ptr(), data.len());
+        assert_eq!(atomicslice_len(atomic_slice), 3);
+
+        let read = atomicslice_read_begin(atomic_slice);
+        assert!(atomicslice_write(atomic_slice, [4.0, 5.0, 6.0].as_ptr(), 3));
+        assert!(!atomicslice_write(atomic_slice, [7.0].as_ptr(), 1));
+        assert_eq!(
+            std::slice::from_raw_parts(read.data, read.len),
+            [1.0, 2.0, 3.0]
+        );
+        assert_eq!(read.version, 0);
+        atomicslice_read_end(read);
+
+        let read = atomicslice_read_begin(atomic_slice);
+        assert_eq!(
+            std::slice::from_raw_parts(read.data, read.len),
+            [4.0, 5.0, 6.0]
+        );
+        assert_eq!(atomicslice_version(atomic_slice), 1);
+        atomicslice_read_end(read);
+
+        atomicslice_free(atomic_slice);
+        atomicslice_free(std::ptr::null_mut());
+
+        let empty = atomicslice_new(std::ptr::null(), 0);
+        assert_eq!(atomicslice_len(empty), 0);
+        atomicslice_free(empty);
+    }
+}
+
+#[test]
+fn test_codegen() {
+    use crate::codegen::{self, StatusLayout};
+    use crate::sync::{AtomicStatus, Ordering};
+
+    type L = StatusLayout<3>;
+
+    let atomic_slice = AtomicSlice::<u32, 3>::new_with_buffers(vec![1, 2]);
+    assert_eq!(
+        codegen::data_ptr(&atomic_slice),
+        codegen::partition_ptr(&atomic_slice, 0)
+    );
+    let status_ptr = codegen::status_ptr(&atomic_slice);
+    let status = unsafe { &*(status_ptr as *const AtomicStatus) };
+
+    // Emulate the read protocol as generated code would do it
+    let index = (status.load(Ordering::Relaxed) & L::INDEX_MASK) as u8;
+    let previous = status.fetch_add(L::inc(index), Ordering::Acquire);
+    assert_eq!((previous & L::INDEX_MASK) as u8, index);
+    assert_eq!((previous >> L::count_shift(index)) & L::COUNT_MASK, 0);
+
+    atomic_slice.write(&[3, 4]);
+    let data = unsafe {
+        std::slice::from_raw_parts(
+            codegen::partition_ptr(&atomic_slice, index),
+            atomic_slice.len(),
+        )
+    };
+    assert_eq!(data, [1, 2]);
+
+    let previous = status.fetch_sub(L::inc(index), Ordering::Release);
+    assert_eq!(previous & L::WRITER_PARKED, 0);
+    assert_eq!(previous & !L::VALID_MASK, 0);
+
+    // The same through the provided functions
+    unsafe {
+        let index = codegen::acquire::<3>(status_ptr);
+        let data = codegen::partition_ptr(&atomic_slice, index);
+        assert_eq!(*data.add(1), 4);
+        assert_eq!(
+            (status.load(Ordering::Relaxed) >> L::count_shift(index)) & L::COUNT_MASK,
+            1
+        );
+        codegen::release::<3>(status_ptr, index);
+        codegen::wake_writers(status_ptr);
+    }
+    assert_eq!(
+        status.load(Ordering::Relaxed) & (L::INC_ALL * L::COUNT_MASK),
+        0
+    );
+}
+
+#[cfg(feature = "cxx")]
+#[test]
+fn test_cxx_bridge() {
+    use crate::cpp::*;
+
+    let atomic_slice = new_atomic_slice_f32(&[1.0, 2.0]);
+    let handle = atomic_slice.clone_handle();
+    let guard = atomic_slice.read();
+    assert!(handle.write(&[3.0, 4.0]));
+    assert!(!handle.write(&[5.0]));
+    assert_eq!(guard.data(), [1.0, 2.0]);
+    assert_eq!(guard.version(), 0);
+    drop(atomic_slice);
+    drop(guard);
+    assert_eq!(handle.read().data(), [3.0, 4.0]);
+    assert_eq!(handle.version(), 1);
+
+    let bytes = new_atomic_slice_u8(b"abc");
+    assert_eq!(bytes.len(), 3);
+    assert!(bytes.write(b"xyz"));
+    assert_eq!(bytes.read().data(), b"xyz");
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn test_write_stats() {
+    let atomic_slice = AtomicSlice::new(vec![0; 4]);
+    atomic_slice.write(&[1; 4]);
+    let stats = atomic_slice.write_stats();
+    assert_eq!(stats.writes, 1);
+    assert_eq!(stats.lock.waits, 0);
+    assert_eq!(stats.drain.waits, 0);
+
+    // A writer waits for a reader holding onto the other partition
+    let guard = atomic_slice.read();
+    atomic_slice.write(&[2; 4]);
+    std::thread::scope(|s| {
+        s.spawn(|| atomic_slice.write(&[3; 4]));
+        std::thread::sleep(Duration::from_millis(50));
+        drop(guard);
+    });
+
+    let stats = atomic_slice.write_stats();
+    assert_eq!(stats.writes, 3);
+    assert_eq!(stats.drain.waits, 1);
+    assert!(stats.drain.spins > 0);
+    assert!(stats.drain.longest >= Duration::from_millis(50));
+    assert_eq!(stats.drain.total, stats.drain.longest);
+
+    atomic_slice.reset_write_stats();
+    assert_eq!(atomic_slice.write_stats(), WriteStats::default());
+}
+
+/// Collects the messages of all events, for checking what is traced
+#[cfg(feature = "tracing")]
+#[derive(Default)]
+struct EventCollector(std::sync::Mutex<Vec<String>>);
+
+#[cfg(feature = "tracing")]
+impl tracing::Subscriber for EventCollector {
+    fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        tracing::span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        struct Message<'a>(&'a mut String);
+
+        impl tracing::field::Visit for Message<'_> {
+            fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+                if field.name() == "message" {
+                    *self.0 = format!("{:?}", value);
+                }
+            }
+        }
+
+        let mut message = String::new();
+        event.record(&mut Message(&mut message));
+        self.0.lock().unwrap().push(message);
+    }
+
+    fn enter(&self, _: &tracing::span::Id) {}
+
+    fn exit(&self, _: &tracing::span::Id) {}
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn test_tracing() {
+    let collector = Arc::new(EventCollector::default());
+    tracing::subscriber::with_default(Arc::clone(&collector), || {
+        let atomic_slice = AtomicSlice::new(vec![0; 4]);
+        atomic_slice.write(&[1; 4]);
+
+        let guard = atomic_slice.read();
+        atomic_slice.write(&[2; 4]);
+        std::thread::scope(|s| {
+            let writer = s.spawn(|| {
+                tracing::subscriber::with_default(Arc::clone(&collector), || {
+                    atomic_slice.write(&[3; 4]);
+                })
+            });
+            std::thread::sleep(Duration::from_millis(10));
+            drop(guard);
+            writer.join().unwrap();
+        });
+    });
+
+    let messages = collector.0.lock().unwrap();
+    let count = |message: &str| messages.iter().filter(|m| *m == message).count();
+    assert_eq!(count("published"), 3);
+    assert_eq!(count("writer finished"), 3);
+    assert_eq!(count("writer waited for readers to release a slice"), 1);
+    #[cfg(feature = "tracing-reads")]
+    {
+        assert_eq!(count("acquired read lock"), 1);
+        assert_eq!(count("released read lock"), 1);
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+#[test]
+fn test_new_in() {
+    use allocator_api2::alloc::{AllocError, Allocator, Global};
+    use core::{alloc::Layout, ptr::NonNull};
+
+    /// Keeps track of the number of bytes currently allocated through it
+    struct Counting(&'static AtomicUsize);
+
+    unsafe impl Allocator for Counting {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.0.fetch_add(layout.size(), Ordering::Relaxed);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.0.fetch_sub(layout.size(), Ordering::Relaxed);
+            Global.deallocate(ptr, layout);
+        }
+    }
+
+    static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+    let atomic_slice: AtomicSlice<String, 3> =
+        AtomicSlice::new_in(vec!["a".to_string(), "b".to_string()], Counting(&ALLOCATED));
+    assert_eq!(
+        ALLOCATED.load(Ordering::Relaxed),
+        6 * std::mem::size_of::<String>()
+    );
+    atomic_slice.write(&["c".to_string(), "d".to_string()]);
+    assert_eq!(*atomic_slice.read(), ["c", "d"]);
+    drop(atomic_slice);
+    assert_eq!(ALLOCATED.load(Ordering::Relaxed), 0);
+
+    let mut data = allocator_api2::vec::Vec::new_in(Counting(&ALLOCATED));
+    data.extend([1_u32, 2, 3]);
+    let atomic_slice: AtomicSlice<u32> = AtomicSlice::from_vec_in(data);
+    assert_eq!(ALLOCATED.load(Ordering::Relaxed), 24);
+    atomic_slice.write(&[4, 5, 6]);
+    assert_eq!(atomic_slice.into_vec(), [4, 5, 6]);
+    assert_eq!(ALLOCATED.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn test_cached_reader() {
+    let atomic_slice = AtomicSlice::new(vec!["a".to_string(), "b".to_string()]);
+    let status = atomic_slice.protocol.status();
+    let mut reader = atomic_slice.cached_reader();
+    assert_eq!(reader.version(), 0);
+    assert!(!reader.is_stale());
+
+    // No read lock is held while the copy is borrowed
+    let latest = reader.latest();
+    assert_eq!(latest, ["a", "b"]);
+    assert_eq!(status.load(Ordering::Relaxed) & !Layout::<2>::INDEX_MASK, 0);
+    atomic_slice.write(&["c".to_string(), "d".to_string()]);
+    atomic_slice.write(&["e".to_string(), "f".to_string()]);
+    assert_eq!(latest, ["a", "b"]);
+
+    assert!(reader.is_stale());
+    assert_eq!(reader.cached(), ["a", "b"]);
+    assert_eq!(reader.latest(), ["e", "f"]);
+    assert_eq!(reader.version(), 2);
+    assert!(!reader.is_stale());
+}
+
+#[test]
+fn test_copy_if_changed() {
+    let atomic_slice = AtomicSlice::new(vec![1, 2, 3]);
+    let mut local = vec![9; 5];
+    let mut last_version = u64::MAX;
+
+    assert!(atomic_slice.copy_if_changed(&mut local, &mut last_version));
+    assert_eq!(local, [1, 2, 3]);
+    assert_eq!(last_version, 0);
+    assert!(!atomic_slice.copy_if_changed(&mut local, &mut last_version));
+
+    atomic_slice.write(&[4, 5, 6]);
+    atomic_slice.write(&[7, 8, 9]);
+    local.clear();
+    assert!(!atomic_slice.copy_if_changed(&mut local, &mut 2));
+    assert!(local.is_empty());
+    assert!(atomic_slice.copy_if_changed(&mut local, &mut last_version));
+    assert_eq!(local, [7, 8, 9]);
+    assert_eq!(last_version, 2);
+}
+
+#[test]
+fn test_read_with() {
+    let atomic_slice = AtomicSlice::new(vec![1, 2, 3]);
+    let status = atomic_slice.protocol.status();
+
+    let sum: i32 = atomic_slice.read_with(|data| {
+        assert_eq!(Layout::<2>::use_count(0, status.load(Ordering::Relaxed)), 1);
+        data.iter().sum()
+    });
+    assert_eq!(sum, 6);
+    assert_eq!(Layout::<2>::use_count(0, status.load(Ordering::Relaxed)), 0);
+
+    atomic_slice.write(&[4, 5, 6]);
+    assert_eq!(atomic_slice.read_with(|data| data.to_vec()), [4, 5, 6]);
+}
+
+#[test]
+fn test_guard_into_raw() {
+    let atomic_slice = AtomicSlice::new(vec![1_u16, 2, 3]);
+    let status = atomic_slice.protocol.status();
+
+    let raw = atomic_slice.read().into_raw();
+    assert_eq!(raw.len, 3);
+    assert_eq!(raw.version, 0);
+    assert_eq!(
+        Layout::<2>::use_count(raw.slice, status.load(Ordering::Relaxed)),
+        1
+    );
+
+    // The partition stays locked while only the raw lock is around
+    atomic_slice.write(&[4, 5, 6]);
+    assert_eq!(unsafe { *raw.data.add(2) }, 3);
+
+    let guard = unsafe { crate::AtomicSliceReadGuard::from_raw(&atomic_slice, raw) };
+    assert_eq!(*guard, [1, 2, 3]);
+    assert_eq!(guard.version(), 0);
+    drop(guard);
+    assert_eq!(
+        Layout::<2>::use_count(raw.slice, status.load(Ordering::Relaxed)),
+        0
+    );
+    atomic_slice.write(&[7, 8, 9]);
+    assert_eq!(*atomic_slice.read(), [7, 8, 9]);
+}
+
+#[test]
+fn test_guards_send_sync() {
+    fn assert_send_sync<X: Send + Sync>(_: &X) {}
+
+    let atomic_slice = Arc::new(AtomicSlice::new(vec![1.0_f32, 2.0, 3.0, 4.0]));
+    let guard = atomic_slice.read();
+    assert_send_sync(&guard);
+    assert_send_sync(&atomic_slice.read().slice(1..));
+    assert_send_sync(&atomic_slice.read_arc());
+
+    // Halves of the same snapshot are summed on different threads, and the
+    // read locks are released there
+    let (front, back) = guard.split_at(2);
+    let sums = std::thread::scope(|scope| {
+        let front = scope.spawn(move || front.iter().sum::<f32>());
+        let back = scope.spawn(move || back.iter().sum::<f32>());
+        (front.join().unwrap(), back.join().unwrap())
+    });
+    assert_eq!(sums, (3.0, 7.0));
+
+    let arc_guard = atomic_slice.read_arc();
+    std::thread::spawn(move || assert_eq!(arc_guard.len(), 4))
+        .join()
+        .unwrap();
+    atomic_slice.write(&[5.0; 4]);
+    assert_eq!(*atomic_slice.read(), [5.0; 4]);
+}
+
+#[test]
+fn test_unwind_safe() {
+    use std::panic::{RefUnwindSafe, UnwindSafe};
+
+    fn assert_unwind_safe<X: UnwindSafe + RefUnwindSafe>(_: &X) {}
+
+    #[derive(Debug, PartialEq)]
+    struct Fragile(u32);
+
+    impl Clone for Fragile {
+        fn clone(&self) -> Fragile {
+            assert!(self.0 != 13, "unlucky");
+            Fragile(self.0)
+        }
+    }
+
+    let atomic_slice = Arc::new(AtomicSlice::new(vec![Fragile(1), Fragile(2)]));
+    assert_unwind_safe(&atomic_slice);
+    assert_unwind_safe(&atomic_slice.read());
+    assert_unwind_safe(&atomic_slice.read_arc());
+    assert_unwind_safe(&AtomicValue::new(0_u32));
+    assert_unwind_safe(&AtomicVec::new(vec![0_u32]));
+    assert_unwind_safe(&AtomicArcSlice::new(Arc::from(vec![0_u32])));
+    assert_unwind_safe(&AtomicSliceInline::new_boxed(vec![0_u32]));
+    assert_unwind_safe(&ShardedAtomicSlice::<u32, 2>::new(vec![0_u32]));
+
+    // No AssertUnwindSafe needed
+    let write = std::panic::catch_unwind({
+        let atomic_slice = Arc::clone(&atomic_slice);
+        move || atomic_slice.write(&[Fragile(3), Fragile(13)])
+    });
+    assert!(write.is_err());
+    assert_eq!(*atomic_slice.read(), [Fragile(1), Fragile(2)]);
+    #[cfg(feature = "std")]
+    assert!(atomic_slice.is_poisoned());
+
+    atomic_slice.write(&[Fragile(3), Fragile(4)]);
+    assert_eq!(*atomic_slice.read(), [Fragile(3), Fragile(4)]);
+}
+
+#[test]
+fn test_guard_slice_traits() {
+    fn sum(data: impl AsRef<[u32]>) -> u32 {
+        data.as_ref().iter().sum()
+    }
+
+    fn contains(
+        set: &std::collections::HashSet<Box<[u32]>>,
+        data: &impl std::borrow::Borrow<[u32]>,
+    ) -> bool {
+        set.contains(data.borrow())
+    }
+
+    let atomic_slice = Arc::new(AtomicSlice::new(vec![1, 2, 3]));
+    let guard = atomic_slice.read();
+    assert_eq!(sum(&*guard), 6);
+    assert_eq!(sum(atomic_slice.read()), 6);
+    assert_eq!(sum(atomic_slice.read().slice(1..)), 5);
+    assert_eq!(sum(atomic_slice.read_arc()), 6);
+
+    let set = std::collections::HashSet::from([vec![1, 2, 3].into_boxed_slice()]);
+    assert!(contains(&set, &guard));
+
+    let mut doubled = Vec::new();
+    for x in &guard {
+        doubled.push(x * 2);
+    }
+    assert_eq!(doubled, [2, 4, 6]);
+    assert_eq!((&atomic_slice.read_arc()).into_iter().count(), 3);
+}
+
+#[test]
+fn test_guard_slice_and_split_at() {
+    let atomic_slice = AtomicSlice::new(vec![1, 2, 3, 4, 5]);
+    let status = atomic_slice.protocol.status();
+
+    let middle = atomic_slice.read().slice(1..4);
+    assert_eq!(*middle, [2, 3, 4]);
+    assert_eq!(*middle.slice(1..), [3, 4]);
+    assert_eq!(*atomic_slice.read().slice(..=1), [1, 2]);
+
+    let (front, back) = atomic_slice.read().split_at(2);
+    assert_eq!(*front, [1, 2]);
+    assert_eq!(*back, [3, 4, 5]);
+    assert_eq!(Layout::<2>::use_count(0, status.load(Ordering::Relaxed)), 2);
+    atomic_slice.write(&[6, 7, 8, 9, 10]);
+    drop(front);
+    let (first, rest) = back.split_at(1);
+    drop(rest);
+    assert_eq!(*first, [3]);
+    assert_eq!(first.version(), 0);
+    drop(first);
+    assert_eq!(Layout::<2>::use_count(0, status.load(Ordering::Relaxed)), 0);
+
+    let (all, none) = atomic_slice.read().split_at(5);
+    assert_eq!(*all, [6, 7, 8, 9, 10]);
+    assert!(none.is_empty());
+}
+
+#[test]
+fn test_new_aligned() {
+    let atomic_slice: AtomicSlice<f32, 3> = AtomicSlice::new_aligned(vec![1.0; 10], 64);
+    assert_eq!(crate::codegen::pitch(&atomic_slice), 16);
+    for i in 0..4 {
+        let guard = atomic_slice.read();
+        assert_eq!(guard.as_ptr() as usize % 64, 0);
+        assert_eq!(*guard, [i as f32 + 1.0; 10]);
+        drop(guard);
+        atomic_slice.write_with(|s| {
+            assert_eq!(s.as_ptr() as usize % 64, 0);
+            s.fill(i as f32 + 2.0);
+        });
+    }
+    assert_eq!(atomic_slice.into_vec(), [5.0; 10]);
+
+    // Elements whose size isn't a power of two
+    let atomic_slice: AtomicSlice<[u8; 3]> =
+        AtomicSlice::new_aligned(vec![[1, 2, 3], [4, 5, 6]], 16);
+    assert_eq!(crate::codegen::pitch(&atomic_slice), 16);
+    atomic_slice.write(&[[7, 8, 9], [0, 0, 0]]);
+    assert_eq!(atomic_slice.read().as_ptr() as usize % 16, 0);
+    assert_eq!(*atomic_slice.read(), [[7, 8, 9], [0, 0, 0]]);
+
+    let empty: AtomicSlice<String> = AtomicSlice::new_aligned(Vec::new(), 32);
+    assert_eq!(empty.read().as_ptr() as usize % 32, 0);
+    let unit: AtomicSlice<()> = AtomicSlice::new_aligned(vec![(); 5], 8);
+    assert_eq!(unit.read().len(), 5);
+}
+
+#[test]
+fn test_from_fn() {
+    let atomic_slice: AtomicSlice<String, 3> = AtomicSlice::from_fn(3, |i| i.to_string());
+    assert_eq!(*atomic_slice.read(), ["0", "1", "2"]);
+    atomic_slice.write_with(|s| assert_eq!(s, ["0", "1", "2"]));
+
+    let mut calls = 0;
+    let empty: AtomicSlice<u8> = AtomicSlice::from_fn(0, |_| {
+        calls += 1;
+        0
+    });
+    assert!(empty.is_empty());
+    assert_eq!(calls, 0);
+}
+
+#[test]
+fn test_from_elem() {
+    let atomic_slice: AtomicSlice<String> = AtomicSlice::from_elem("x".to_string(), 3);
+    assert_eq!(*atomic_slice.read(), ["x", "x", "x"]);
+    atomic_slice.write_with(|s| assert_eq!(s, ["x", "x", "x"]));
+
+    let empty: AtomicSlice<u8, 4> = AtomicSlice::from_elem(1, 0);
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn test_new_uninit() {
+    let uninit = AtomicSlice::<String, 3>::new_uninit(2);
+    assert_eq!(uninit.len(), 2);
+    let atomic_slice = uninit.write(&["a".to_string(), "b".to_string()]);
+    assert_eq!(*atomic_slice.read(), ["a", "b"]);
+    for i in 0..3 {
+        atomic_slice.write_with(|s| assert_eq!(s, ["a", "b"]));
+        assert_eq!(atomic_slice.version(), i + 1);
+    }
+
+    let empty: AtomicSlice<u8> = AtomicSlice::new_uninit(0).write(&[]);
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn test_new_in_buffer() {
+    let buffer: &'static mut [String] = Box::leak(Box::new([
+        "a".to_string(),
+        "b".to_string(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+    ]));
+    let atomic_slice: AtomicSlice<String, 3> = AtomicSlice::new_in_buffer(buffer);
+    assert_eq!(atomic_slice.len(), 2);
+    assert_eq!(*atomic_slice.read(), ["a", "b"]);
+    atomic_slice.write(&["c".to_string(), "d".to_string()]);
+    atomic_slice.write(&["e".to_string(), "f".to_string()]);
+    assert_eq!(*atomic_slice.read(), ["e", "f"]);
+    assert_eq!(atomic_slice.into_vec(), ["e", "f"]);
+
+    let atomic_slice: AtomicSlice<u8> = AtomicSlice::from_boxed_buffer(Box::new([1, 2, 0, 0]));
+    assert_eq!(*atomic_slice.read(), [1, 2]);
+    atomic_slice.write(&[3, 4]);
+    assert_eq!(*atomic_slice.read(), [3, 4]);
+
+    let result = std::panic::catch_unwind(|| {
+        AtomicSlice::<u8>::from_boxed_buffer(Box::new([1, 2, 3]));
+    });
+    assert!(result.is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_memory_options() {
+    use crate::MemoryOptions;
+
+    // Large enough to span several pages
+    let data: Vec<u64> = (0..10_000).collect();
+
+    let prefaulted: AtomicSlice<u64> = AtomicSlice::new_with_memory(
+        data.clone(),
+        MemoryOptions {
+            prefault: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(*prefaulted.read(), *data);
+
+    let options = MemoryOptions {
+        prefault: true,
+        lock: true,
+    };
+    let locked = AtomicSlice::<u64, 3>::new_with_memory(data.clone(), options);
+    if cfg!(all(feature = "mlock", unix)) {
+        let locked = locked.unwrap();
+        locked.write(&[7; 10_000]);
+        assert_eq!(locked.into_vec(), [7; 10_000]);
+
+        let empty: AtomicSlice<u64> = AtomicSlice::new_with_memory(Vec::new(), options).unwrap();
+        assert!(empty.is_empty());
+    } else {
+        assert_eq!(locked.unwrap_err().kind(), std::io::ErrorKind::Unsupported);
+    }
+
+    // Small locked slices don't share any pages, so that unlocking one of
+    // them leaves the other locked
+    #[cfg(all(feature = "mlock", unix))]
+    {
+        let page = crate::memory::page_size();
+        let first = AtomicSlice::<u8>::new_with_memory(vec![1; 10], options).unwrap();
+        let second = AtomicSlice::<u8>::new_with_memory(vec![2; 10], options).unwrap();
+        assert_eq!(first.data.as_ptr().addr() % page, 0);
+        assert_eq!(second.data.as_ptr().addr() % page, 0);
+        assert_ne!(first.data.as_ptr(), second.data.as_ptr());
+        drop(first);
+        assert_eq!(*second.read(), [2; 10]);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_zeroizing() {
+    use crate::ZeroizingAtomicSlice;
+
+    static ZEROIZED: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Secret(u8);
+
+    impl zeroize::Zeroize for Secret {
+        fn zeroize(&mut self) {
+            self.0 = 0;
+            ZEROIZED.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    // The initial vector and the inactive partition are wiped
+    let secret = Arc::new(ZeroizingAtomicSlice::new(vec![Secret(1); 4]));
+    assert_eq!(ZEROIZED.swap(0, Ordering::Relaxed), 8);
+    assert_eq!(*secret.read(), [Secret(1), Secret(1), Secret(1), Secret(1)]);
+
+    secret.write(&[Secret(2), Secret(2), Secret(2), Secret(2)]);
+    assert_eq!(ZEROIZED.swap(0, Ordering::Relaxed), 4);
+    assert_eq!(secret.version(), 1);
+
+    // Writing into the wiped partition sees zeroes
+    secret.write_with(|s| {
+        assert_eq!(s, [Secret(0), Secret(0), Secret(0), Secret(0)]);
+        s[0] = Secret(3);
+    });
+    assert_eq!(ZEROIZED.swap(0, Ordering::Relaxed), 4);
+
+    // A partition that is still being read from is only wiped once released
+    let guard = secret.read();
+    let writer = std::thread::spawn({
+        let secret = Arc::clone(&secret);
+        move || secret.write(&[Secret(4), Secret(4), Secret(4), Secret(4)])
+    });
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    assert_eq!(*guard, [Secret(3), Secret(0), Secret(0), Secret(0)]);
+    drop(guard);
+    writer.join().unwrap();
+    assert_eq!(ZEROIZED.swap(0, Ordering::Relaxed), 4);
+    assert_eq!(*secret.read(), [Secret(4), Secret(4), Secret(4), Secret(4)]);
+
+    // Dropping wipes every partition
+    drop(Arc::into_inner(secret).unwrap());
+    assert_eq!(ZEROIZED.load(Ordering::Relaxed), 8);
+}
+
+/// Frees memory like the system allocator, but first counts the blocks that
+/// still hold `SECRET` while the freeing thread is watching for them
+#[cfg(feature = "zeroize")]
+struct SecretFinder;
+
+#[cfg(feature = "zeroize")]
+const SECRET: &[u8] = b"correct horse battery staple";
+
+#[cfg(feature = "zeroize")]
+std::thread_local! {
+    static SECRETS_FREED: std::cell::Cell<Option<usize>> = const { std::cell::Cell::new(None) };
+}
+
+#[cfg(feature = "zeroize")]
+unsafe impl std::alloc::GlobalAlloc for SecretFinder {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        std::alloc::System.alloc(layout)
+    }
+
+    // Reallocating goes through here as well, since realloc isn't overridden
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+        let _ = SECRETS_FREED.try_with(|freed| {
+            if let Some(n) = freed.get() {
+                let block = std::slice::from_raw_parts(ptr, layout.size());
+                if block.windows(SECRET.len()).any(|w| w == SECRET) {
+                    freed.set(Some(n + 1));
+                }
+            }
+        });
+        std::alloc::System.dealloc(ptr, layout);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[global_allocator]
+static SECRET_FINDER: SecretFinder = SecretFinder;
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_zeroizing_leaves_no_copies() {
+    use crate::ZeroizingAtomicSlice;
+
+    SECRETS_FREED.with(|freed| freed.set(Some(0)));
+    for _ in 0..4 {
+        // The vector has no room to spare, just like one that was cloned
+        let secret = ZeroizingAtomicSlice::<u8, 3>::new_with_buffers(SECRET.to_vec());
+        assert_eq!(*secret.read(), *SECRET);
+        secret.write(&[0; SECRET.len()]);
+        secret.write(SECRET);
+        drop(secret);
+    }
+    let secret = ZeroizingAtomicSlice::new(SECRET.to_vec());
+    drop(secret);
+    assert_eq!(SECRETS_FREED.with(|freed| freed.take()), Some(0));
+}
+
+#[test]
+fn test_writers_first_come_first_served() {
+    let atomic_slice = Arc::new(AtomicSlice::new(vec![0_usize; 4]));
+    let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let lock = atomic_slice.protocol.lock_writer();
+    let writers: Vec<_> = (0..4)
+        .map(|i| {
+            let writer = std::thread::spawn({
+                let atomic_slice = Arc::clone(&atomic_slice);
+                let order = Arc::clone(&order);
+                move || atomic_slice.write_with(|_| order.lock().unwrap().push(i))
+            });
+            // Let each writer line up before starting the next one
+            while atomic_slice.protocol.writers() != i + 2 {
+                std::thread::yield_now();
+            }
+            writer
+        })
+        .collect();
+
+    // Nobody can jump the queue
+    assert!(atomic_slice.protocol.try_lock_writer().is_none());
+    drop(lock);
+    for writer in writers {
+        writer.join().unwrap();
+    }
+    assert_eq!(*order.lock().unwrap(), [0, 1, 2, 3]);
+    assert_eq!(atomic_slice.protocol.writers(), 0);
+    assert!(atomic_slice.protocol.try_lock_writer().is_some());
+}
+
+#[test]
+fn test_write_with_overlapping_readers() {
+    let atomic_slice = Arc::new(AtomicSlice::new(vec![0_u32; 4]));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    // Each reader takes a new read lock before letting go of the previous one
+    let readers: Vec<_> = (0..2)
+        .map(|_| {
+            let atomic_slice = Arc::clone(&atomic_slice);
+            let stop = Arc::clone(&stop);
+            std::thread::spawn(move || {
+                let mut guard = atomic_slice.read();
+                while !stop.load(Ordering::Relaxed) {
+                    let next = atomic_slice.read();
+                    assert!(next[0] >= guard[0]);
+                    guard = next;
+                }
+            })
+        })
+        .collect();
+
+    for value in 1..=100 {
+        atomic_slice.write(&[value; 4]);
+    }
+    stop.store(true, Ordering::Relaxed);
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    assert_eq!(*atomic_slice.read(), [100; 4]);
+}
+
+#[cfg(any(debug_assertions, feature = "paranoid"))]
+#[test]
+fn test_corrupted_status_panics() {
+    // With a 32-bit status word, every bit is used with two partitions
+    let atomic_slice = AtomicSlice::<u8, 3>::new_with_buffers(vec![0; 4]);
+    unsafe { (*atomic_slice.raw_status()).store(!Layout::<3>::VALID_MASK, Ordering::SeqCst) };
+
+    let read = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        atomic_slice.read();
+    }));
+    let message = *read.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.starts_with("Corrupted AtomicSlice status word"));
+}
+
+#[test]
+fn test_introspection() {
+    let atomic_slice = AtomicSlice::<u8, 3>::new_with_buffers(vec![0; 4]);
+    assert_eq!(atomic_slice.active_slice(), 0);
+    assert_eq!(atomic_slice.reader_counts(), [0, 0, 0]);
+    assert!(!atomic_slice.is_writing());
+
+    let first = atomic_slice.read();
+    let second = atomic_slice.read();
+    atomic_slice.write(&[1; 4]);
+    let third = atomic_slice.read();
+    let active = atomic_slice.active_slice();
+    assert_ne!(active, 0);
+    let mut expected = [0; 3];
+    expected[0] = 2;
+    expected[active] = 1;
+    assert_eq!(atomic_slice.reader_counts(), expected);
+
+    atomic_slice.write_with(|_| assert!(atomic_slice.is_writing()));
+    assert!(!atomic_slice.is_writing());
+    drop((first, second, third));
+    assert_eq!(atomic_slice.reader_counts(), [0, 0, 0]);
+}
+
+#[cfg(feature = "debug-dump")]
+#[test]
+fn test_debug_dump() {
+    let atomic_slice = AtomicSlice::new(vec![1_u8, 2, 3]);
+    atomic_slice.write(&[4, 5, 6]);
+    let guard = atomic_slice.read();
+
+    let dump = atomic_slice.debug_dump();
+    let lines: Vec<_> = dump.lines().collect();
+    assert_eq!(
+        lines[0],
+        "AtomicSlice with 2 partitions of 3 elements, 3 elements apart"
+    );
+    assert!(lines[1].starts_with("status word: 0x"));
+    assert_eq!(lines[2], "  active partition: 1");
+    assert_eq!(lines[3], "  writer parked: false");
+    assert_eq!(lines[4], "version: 1");
+    assert_eq!(
+        lines[5],
+        "partition 0 (stale, version 0, 0 readers): [1, 2, 3]"
+    );
+    assert_eq!(
+        lines[6],
+        "partition 1 (active, version 1, 1 readers): [4, 5, 6]"
+    );
+    drop(guard);
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn test_archive() {
+    let atomic_slice = AtomicSlice::new(vec![String::from("a"), String::from("b")]);
+    let bytes = atomic_slice.to_archive().unwrap();
+
+    let copy = AtomicSlice::<String>::from_archive(&bytes).unwrap();
+    assert_eq!(*copy.read(), ["a", "b"]);
+
+    atomic_slice.write(&[String::from("c"), String::from("d")]);
+    atomic_slice.write_from_archive(&bytes).unwrap();
+    assert_eq!(*atomic_slice.read(), ["a", "b"]);
+    assert_eq!(atomic_slice.version(), 2);
+
+    let longer = AtomicSlice::new(vec![String::new(); 3])
+        .to_archive()
+        .unwrap();
+    assert!(atomic_slice.write_from_archive(&longer).is_err());
+    assert_eq!(atomic_slice.version(), 2);
+
+    assert!(AtomicSlice::<String>::from_archive(&bytes[1..]).is_err());
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn test_snapshot_bytes() {
+    let atomic_slice = AtomicSlice::new(vec![1_u8, 2, 3]);
+    let snapshot = atomic_slice.snapshot_bytes();
+    atomic_slice.write(&[4, 5, 6]);
+    assert_eq!(snapshot, [1, 2, 3][..]);
+    assert_eq!(snapshot.clone().slice(1..), [2, 3][..]);
+    assert_eq!(atomic_slice.snapshot_bytes(), [4, 5, 6][..]);
+}
+
+#[test]
+fn test_snapshot_arc() {
+    let atomic_slice = Arc::new(AtomicSlice::new(vec![String::from("a"); 2]));
+    let first = atomic_slice.snapshot_arc();
+    assert_eq!(*first, ["a", "a"]);
+    assert!(Arc::ptr_eq(&first, &atomic_slice.snapshot_arc()));
+
+    atomic_slice.write(&[String::from("b"), String::from("c")]);
+    let second = atomic_slice.snapshot_arc();
+    assert_eq!(*second, ["b", "c"]);
+    assert!(!Arc::ptr_eq(&first, &second));
+    assert_eq!(*first, ["a", "a"]);
+
+    // Snapshots taken on other threads hold the latest version too
+    let snapshots: Vec<_> = (0..4)
+        .map(|_| {
+            let atomic_slice = Arc::clone(&atomic_slice);
+            std::thread::spawn(move || atomic_slice.snapshot_arc())
+        })
+        .collect();
+    for snapshot in snapshots {
+        let snapshot = snapshot.join().unwrap();
+        assert_eq!(*snapshot, ["b", "c"]);
+    }
+    assert!(Arc::ptr_eq(&second, &atomic_slice.snapshot_arc()));
+
+    // Changes made through `get_mut` don't publish a new version, and must
+    // not be hidden by the cached snapshot
+    let mut atomic_slice = Arc::into_inner(atomic_slice).unwrap();
+    atomic_slice.get_mut()[0] = String::from("d");
+    let third = atomic_slice.snapshot_arc();
+    assert_eq!(*third, ["d", "c"]);
+    assert_eq!(*second, ["b", "c"]);
+    assert!(Arc::ptr_eq(&third, &atomic_slice.snapshot_arc()));
+
+    // Elements that can't be shared with other threads can still be shared
+    // within one
+    let atomic_slice = AtomicSlice::new(vec![std::rc::Rc::new(1)]);
+    assert_eq!(*atomic_slice.snapshot_arc()[0], 1);
+}
+
+#[test]
+fn test_write_range() {
+    let atomic_slice = AtomicSlice::<_, 3>::new_with_buffers((0..8_u32).collect());
+    atomic_slice.write_range(2, &[20, 30]);
+    assert_eq!(*atomic_slice.read(), [0, 1, 20, 30, 4, 5, 6, 7]);
+    atomic_slice.write_range(6, &[60, 70]);
+    atomic_slice.write_range(0, &[]);
+    atomic_slice.write_range(8, &[]);
+    assert_eq!(*atomic_slice.read(), [0, 1, 20, 30, 4, 5, 60, 70]);
+    assert_eq!(atomic_slice.version(), 4);
+
+    for (offset, data) in [(7, &[1, 2][..]), (9, &[]), (usize::MAX, &[1])] {
+        let write = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            atomic_slice.write_range(offset, data);
+        }));
+        assert!(write.is_err());
+    }
+    assert_eq!(atomic_slice.version(), 4);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_write_timeout() {
+    let atomic_slice = AtomicSlice::new(vec![0_u16; 8]);
+    let timeout = Duration::from_millis(10);
+    assert_eq!(atomic_slice.write_timeout(&[1; 8], timeout), Ok(()));
+
+    let guard = atomic_slice.read();
+    assert_eq!(atomic_slice.write_timeout(&[2; 8], timeout), Ok(()));
+    assert_eq!(
+        atomic_slice.write_timeout(&[3; 8], timeout),
+        Err(WriteTimeout)
+    );
+    assert_eq!(*guard, [1; 8]);
+    drop(guard);
+
+    atomic_slice.write_with(|_| {
+        assert_eq!(
+            atomic_slice.write_timeout(&[4; 8], timeout),
+            Err(WriteTimeout)
+        );
+    });
+
+    assert_eq!(atomic_slice.write_timeout(&[5; 8], timeout), Ok(()));
+    assert_eq!(*atomic_slice.read(), [5; 8]);
+
+    // Without a deadline to reach, writing waits for as long as it takes
+    assert_eq!(atomic_slice.write_timeout(&[6; 8], Duration::MAX), Ok(()));
+    std::thread::scope(|s| {
+        let guard = atomic_slice.read();
+        atomic_slice.write(&[7; 8]);
+        s.spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            assert_eq!(*guard, [6; 8]);
+        });
+        assert_eq!(atomic_slice.write_timeout(&[8; 8], Duration::MAX), Ok(()));
+    });
+    assert_eq!(*atomic_slice.read(), [8; 8]);
+}
+
+#[test]
+fn test_backoff() {
+    static ARRAY: AtomicArray<u8, 2> = AtomicArray::new([0; 2]).with_backoff(Backoff::Exponential);
+    ARRAY.write(&[1, 2]);
+    assert_eq!(*ARRAY.read(), [1, 2]);
+    assert_eq!(Backoff::DEFAULT, Backoff::default());
+
+    let backoffs = [
+        Backoff::Spin,
+        Backoff::Exponential,
+        #[cfg(feature = "std")]
+        Backoff::SpinThenYield,
+        #[cfg(feature = "std")]
+        Backoff::Park,
+    ];
+
+    for backoff in backoffs {
+        let mut atomic_slice = AtomicSlice::new(vec![0_u8; 4]);
+        atomic_slice.set_backoff(backoff);
+
+        std::thread::scope(|s| {
+            let (tx, rx) = std::sync::mpsc::channel();
+            let atomic_slice = &atomic_slice;
+            s.spawn(move || {
+                let guard = atomic_slice.read();
+                tx.send(()).unwrap();
+                std::thread::sleep(std::time::Duration::from_millis(10));
+                assert_eq!(*guard, [0; 4]);
+            });
+            rx.recv().unwrap();
+            atomic_slice.write(&[1; 4]);
+
+            // The next write has to wait until the old data is released
+            atomic_slice.write(&[2; 4]);
+        });
+        assert_eq!(*atomic_slice.read(), [2; 4]);
+
+        let status = unsafe { (*atomic_slice.raw_status()).load(Ordering::SeqCst) };
+        assert_eq!(status & Layout::<2>::WRITER_PARKED, 0);
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_park() {
+    let mut atomic_slice = AtomicSlice::new(vec![0_u32; 4]);
+    atomic_slice.set_backoff(Backoff::Park);
+    let mut sharded = ShardedAtomicSlice::<u32, 4>::new(vec![0; 4]);
+    sharded.set_backoff(Backoff::Park);
+
+    // Competing writers park until the current one is done
+    std::thread::scope(|s| {
+        atomic_slice.write_with(|slice| {
+            let writer = s.spawn(|| atomic_slice.write(&[2; 4]));
+            std::thread::sleep(Duration::from_millis(10));
+            assert!(!writer.is_finished());
+            slice.fill(1);
+        });
+    });
+    assert_eq!(*atomic_slice.read(), [2; 4]);
+
+    // Writers park until readers in any shard are done with the old data
+    std::thread::scope(|s| {
+        let guards: Vec<_> = (0..4)
+            .map(|_| s.spawn(|| sharded.read()).join().unwrap())
+            .collect();
+        sharded.write(&[1; 4]);
+        let writer = s.spawn(|| sharded.write(&[2; 4]));
+        for guard in guards {
+            std::thread::sleep(Duration::from_millis(5));
+            assert!(!writer.is_finished());
+            assert_eq!(*guard, [0; 4]);
+        }
+    });
+    assert_eq!(*sharded.read(), [2; 4]);
+
+    // A reader that guessed the wrong slice wakes up a writer which parked
+    // after seeing the reader's temporary use count
+    let status = atomic_slice.protocol.status();
+    let inc = Layout::<2>::inc(1 - atomic_slice.protocol.current_slice());
+    status.fetch_add(inc, Ordering::Acquire);
+    std::thread::scope(|s| {
+        let writer = s.spawn(|| atomic_slice.write(&[3; 4]));
+        while status.load(Ordering::Relaxed) & Layout::<2>::WRITER_PARKED == 0 {
+            std::thread::yield_now();
+        }
+        crate::protocol::undo_acquire::<2>(status, inc);
+        let since = std::time::Instant::now();
+        while !writer.is_finished() && since.elapsed() < Duration::from_secs(10) {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        let woken = writer.is_finished();
+        // Wake the writer up regardless, so that the scope can end
+        crate::park::unpark_all((status as *const crate::sync::AtomicStatus).addr());
+        assert!(woken, "writer wasn't woken up");
+    });
+    assert_eq!(*atomic_slice.read(), [3; 4]);
+}
+
+#[test]
+fn test_split() {
+    let (reader, mut writer) = AtomicSlice::new(vec![0_u32; 64]).split();
+
+    let readers: Vec<std::thread::JoinHandle<()>> = (0..4)
+        .map(|_| {
+            let reader = reader.clone();
+            std::thread::spawn(move || {
+                for _ in 0..10_000 {
+                    let guard = reader.read();
+                    assert_eq!(guard.len(), 64);
+                    assert!(guard.iter().all(|v| *v == guard[0]));
+                }
+            })
+        })
+        .collect();
+
+    let mut data = vec![0_u32; 64];
+    for i in 0..10_000 {
+        data.fill(i);
+        writer.write(&data);
+    }
+
+    for t in readers {
+        t.join().unwrap();
+    }
+
+    assert_eq!(*reader.read(), [9_999; 64]);
+}
+
+#[test]
+fn test_split_writer_scratch() {
+    let (reader, mut writer) = AtomicSlice::new(vec!["a".to_string(), "b".to_string()]).split();
+
+    writer.scratch_mut()[0].push('1');
+    assert_eq!(*reader.read(), ["a", "b"]);
+    writer.publish();
+    assert_eq!(*reader.read(), ["a1", "b"]);
+    assert_eq!(reader.read().version(), 1);
+
+    writer.scratch_mut()[1].push('2');
+    writer.publish();
+    assert_eq!(*reader.read(), ["a1", "b2"]);
+
+    // Writing through other methods replaces the scratch buffer's contents
+    writer.write(&["c".to_string(), "d".to_string()]);
+    assert_eq!(writer.scratch_mut(), ["c", "d"]);
+    writer.publish();
+    assert_eq!(*reader.read(), ["c", "d"]);
+    assert_eq!(reader.read().version(), 4);
+}
+
+#[test]
+fn test_no_default() {
+    // Neither Default nor Copy
+    #[derive(Clone, Debug, PartialEq)]
+    struct Name(Arc<str>);
+
+    let atomic_slice = AtomicSlice::new(vec![Name("a".into()), Name("b".into())]);
+    assert_eq!(*atomic_slice.read(), [Name("a".into()), Name("b".into())]);
+    atomic_slice.write(&[Name("c".into()), Name("d".into())]);
+    assert_eq!(*atomic_slice.read(), [Name("c".into()), Name("d".into())]);
+}
+
+#[test]
+fn test_write_vec() {
+    static CLONES: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug, PartialEq)]
+    struct CountedClone(u32);
+
+    impl Clone for CountedClone {
+        fn clone(&self) -> Self {
+            CLONES.fetch_add(1, Ordering::SeqCst);
+            CountedClone(self.0)
+        }
+    }
+
+    let atomic_slice = AtomicSlice::new(vec![CountedClone(0), CountedClone(0)]);
+    let clones_before = CLONES.load(Ordering::SeqCst);
+    atomic_slice.write_vec(vec![CountedClone(1), CountedClone(2)]);
+    assert_eq!(CLONES.load(Ordering::SeqCst), clones_before);
+    assert_eq!(*atomic_slice.read(), [CountedClone(1), CountedClone(2)]);
+}
+
+#[test]
+fn test_swap() {
+    let atomic_slice = AtomicSlice::new(vec![0_i32; 3]);
+    let mut data = vec![1, 2, 3];
+    atomic_slice.swap(&mut data);
+    assert_eq!(data, [0, 0, 0]);
+    assert_eq!(*atomic_slice.read(), [1, 2, 3]);
+
+    data.copy_from_slice(&[4, 5, 6]);
+    atomic_slice.swap(&mut data);
+    assert_eq!(data, [1, 2, 3]);
+    assert_eq!(*atomic_slice.read(), [4, 5, 6]);
+}
+
+#[test]
+fn test_read_into() {
+    let atomic_slice = AtomicSlice::new(vec![1_u64, 2, 3, 4]);
+    let mut out = [0; 4];
+    atomic_slice.read_into(&mut out);
+    assert_eq!(out, [1, 2, 3, 4]);
+
+    atomic_slice.write(&[5, 6, 7, 8]);
+    atomic_slice.read_into(&mut out);
+    assert_eq!(out, [5, 6, 7, 8]);
+
+    // The read lock is released again, so writing both partitions can proceed
+    assert_eq!(atomic_slice.try_write(&[0; 4]), Ok(()));
+    assert_eq!(atomic_slice.try_write(&[0; 4]), Ok(()));
+}
+
+#[test]
+fn test_to_vec() {
+    let atomic_slice = AtomicSlice::new(vec![1.0_f32, 2.0]);
+    assert_eq!(atomic_slice.to_vec(), vec![1.0, 2.0]);
+    atomic_slice.write(&[3.0, 4.0]);
+    assert_eq!(atomic_slice.to_vec(), vec![3.0, 4.0]);
+}
+
+#[test]
+fn test_read_arc() {
+    let atomic_slice = Arc::new(AtomicSlice::new(vec![7_u8; 32]));
+    let guard = atomic_slice.read_arc();
+
+    // The guard can be sent to another thread and outlive the scope it was created in
+    let handle = std::thread::spawn(move || {
+        assert_eq!(*guard, [7; 32]);
+        guard
+    });
+    atomic_slice.write(&[8; 32]);
+    let guard = handle.join().unwrap();
+    assert_eq!(*guard, [7; 32]);
+    assert_eq!(
+        atomic_slice.try_write(&[9; 32]),
+        Err(TryWriteError::ReadInProgress)
+    );
+    drop(guard);
+    assert_eq!(atomic_slice.try_write(&[9; 32]), Ok(()));
+    assert_eq!(*atomic_slice.read_arc(), [9; 32]);
+}
+
+#[test]
+fn test_map_guard() {
+    let atomic_slice = AtomicSlice::new(vec![0_u32, 1, 2, 3, 4, 5, 6, 7]);
+    let tail = atomic_slice.read().map(|s| &s[4..]);
+    let header = atomic_slice.read().map(|s| std::slice::from_ref(&s[0]));
+    assert_eq!(*tail, [4, 5, 6, 7]);
+    assert_eq!(*header, [0]);
+
+    let last = tail.map(|s| &s[3..]);
+    assert_eq!(*last, [7]);
+
+    // The mapped guards still hold the read lock
+    atomic_slice.write(&[8; 8]);
+    assert_eq!(
+        atomic_slice.try_write(&[9; 8]),
+        Err(TryWriteError::ReadInProgress)
+    );
+    drop(header);
+    assert_eq!(
+        atomic_slice.try_write(&[9; 8]),
+        Err(TryWriteError::ReadInProgress)
+    );
+    drop(last);
+    assert_eq!(atomic_slice.try_write(&[9; 8]), Ok(()));
+}
+
+#[test]
+fn test_clone_guard() {
+    let atomic_slice = AtomicSlice::new(vec![1_u8; 4]);
+    let guard = atomic_slice.read();
+    let clone = guard.clone();
+    atomic_slice.write(&[2; 4]);
+    drop(guard);
+
+    // The clone keeps the same version pinned after the original is dropped
+    assert_eq!(*clone, [1; 4]);
+    assert_eq!(
+        atomic_slice.try_write(&[3; 4]),
+        Err(TryWriteError::ReadInProgress)
+    );
+    let clone_of_clone = clone.clone();
+    drop(clone);
+    assert_eq!(*clone_of_clone, [1; 4]);
+    assert_eq!(
+        atomic_slice.try_write(&[3; 4]),
+        Err(TryWriteError::ReadInProgress)
+    );
+    drop(clone_of_clone);
+    assert_eq!(atomic_slice.try_write(&[3; 4]), Ok(()));
+}
+
+#[test]
+fn test_version() {
+    let atomic_slice = Arc::new(AtomicSlice::new(vec![0_u8; 2]));
+    assert_eq!(atomic_slice.version(), 0);
+    assert_eq!(atomic_slice.read().version(), 0);
+
+    atomic_slice.write(&[1; 2]);
+    let guard = atomic_slice.read();
+    assert_eq!(atomic_slice.version(), 1);
+    assert_eq!(guard.version(), 1);
+
+    atomic_slice.write_with(|s| s.fill(2));
+    assert_eq!(atomic_slice.version(), 2);
+    assert_eq!(guard.version(), 1);
+    assert_eq!(guard.clone().map(|s| &s[1..]).version(), 1);
+    assert_eq!(atomic_slice.read_arc().version(), 2);
+
+    // Failed writes don't change the version
+    assert!(atomic_slice.try_write(&[3; 2]).is_err());
+    assert_eq!(atomic_slice.version(), 2);
+    drop(guard);
+    assert!(atomic_slice.try_write(&[3; 2]).is_ok());
+    assert_eq!(atomic_slice.version(), 3);
+    assert_eq!(atomic_slice.read().version(), 3);
+}
+
+#[test]
+fn test_read_if_newer() {
+    let atomic_slice = AtomicSlice::new(vec![0_u32; 3]);
+    let mut last_seen = 0;
+    assert!(atomic_slice.read_if_newer(&mut last_seen).is_none());
+
+    atomic_slice.write(&[1; 3]);
+    atomic_slice.write(&[2; 3]);
+    let guard = atomic_slice.read_if_newer(&mut last_seen).unwrap();
+    assert_eq!(*guard, [2; 3]);
+    assert_eq!(last_seen, 2);
+    drop(guard);
+    assert!(atomic_slice.read_if_newer(&mut last_seen).is_none());
+    assert_eq!(last_seen, 2);
+
+    atomic_slice.write(&[3; 3]);
+    assert_eq!(*atomic_slice.read_if_newer(&mut last_seen).unwrap(), [3; 3]);
+    assert_eq!(last_seen, 3);
+
+    // Versions from elsewhere are compared the same way as by copy_if_changed
+    let mut local = Vec::new();
+    for start in [u64::MAX, 5, 3] {
+        let mut last_seen = start;
+        let mut last_version = start;
+        assert_eq!(
+            atomic_slice.read_if_newer(&mut last_seen).is_some(),
+            atomic_slice.copy_if_changed(&mut local, &mut last_version)
+        );
+        assert_eq!(last_seen, last_version);
+    }
+    let mut last_seen = 5;
+    assert!(atomic_slice.read_if_newer(&mut last_seen).is_some());
+    assert_eq!(last_seen, 3);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_wait_for_update() {
+    let atomic_slice = Arc::new(AtomicSlice::new(vec![0_u32; 4]));
+    atomic_slice.write(&[1; 4]);
+    assert_eq!(atomic_slice.wait_for_update(0), 1);
+
+    let waiters: Vec<std::thread::JoinHandle<()>> = (0..4)
+        .map(|_| {
+            let atomic_slice = Arc::clone(&atomic_slice);
+            std::thread::spawn(move || {
+                let mut version = 1;
+                while version < 100 {
+                    let new_version = atomic_slice.wait_for_update(version);
+                    assert!(new_version > version);
+                    let guard = atomic_slice.read();
+                    assert!(guard.version() >= new_version);
+                    assert_eq!(*guard, [guard.version() as u32; 4]);
+                    version = new_version;
+                }
+            })
+        })
+        .collect();
+
+    for i in 2..=100 {
+        std::thread::sleep(Duration::from_micros(100));
+        atomic_slice.write(&[i; 4]);
+    }
+
+    for t in waiters {
+        t.join().unwrap();
+    }
+}
+
+#[cfg(feature = "async")]
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    struct ThreadWaker(std::thread::Thread);
+
+    impl std::task::Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Arc::new(ThreadWaker(std::thread::current())).into();
+    let mut cx = std::task::Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        std::thread::park();
+    }
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_changed() {
+    let atomic_slice = Arc::new(AtomicSlice::new(vec![0_u32; 4]));
+
+    let waiter = {
+        let atomic_slice = Arc::clone(&atomic_slice);
+        std::thread::spawn(move || {
+            let mut version = 0;
+            while version < 100 {
+                let changed = atomic_slice.changed();
+                let guard = atomic_slice.read();
+                if guard.version() > version {
+                    // Already changed since last time, don't wait
+                    version = guard.version();
+                    continue;
+                }
+                drop(guard);
+                let new_version = block_on(changed);
+                assert!(new_version > version);
+                version = new_version;
+            }
+        })
+    };
+
+    for i in 1..=100 {
+        std::thread::sleep(Duration::from_micros(100));
+        atomic_slice.write(&[i; 4]);
+    }
+
+    waiter.join().unwrap();
+}
+
+#[cfg(feature = "stream")]
+#[test]
+fn test_subscribe() {
+    use futures_core::Stream;
+
+    fn next<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+        block_on(std::future::poll_fn(|cx| {
+            std::pin::Pin::new(&mut *stream).poll_next(cx)
+        }))
+    }
+
+    let atomic_slice = Arc::new(AtomicSlice::new(vec![0_u32; 4]));
+    let mut subscription = atomic_slice.subscribe();
+    assert_eq!(next(&mut subscription), Some(vec![0; 4]));
+    assert_eq!(subscription.last_seen(), Some(0));
+
+    // Intermediate versions are skipped
+    atomic_slice.write(&[1; 4]);
+    atomic_slice.write(&[2; 4]);
+    assert_eq!(next(&mut subscription), Some(vec![2; 4]));
+    assert_eq!(subscription.last_seen(), Some(2));
+
+    let mut changes_only = atomic_slice.subscribe_from(atomic_slice.version());
+    let writer = {
+        let atomic_slice = Arc::clone(&atomic_slice);
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            atomic_slice.write(&[3; 4]);
+        })
+    };
+    assert_eq!(next(&mut changes_only), Some(vec![3; 4]));
+    assert_eq!(next(&mut subscription), Some(vec![3; 4]));
+    writer.join().unwrap();
+}
+
+#[cfg(not(any(feature = "status-u32", feature = "status-u128")))]
+#[test]
+fn test_default_layout() {
+    use crate::codegen::StatusLayout;
+
+    assert_eq!(Layout::<2>::inc(0), constants::SLICE_1_INC);
+    assert_eq!(Layout::<2>::inc(1), constants::SLICE_2_INC);
+    assert_eq!(Layout::<2>::INC_ALL, constants::INC_ALL_SLICES);
+    assert_eq!(Layout::<2>::WRITER_PARKED, constants::WRITER_PARKED);
+    assert_eq!(Layout::<2>::MAX_COUNT, 0xFFFF);
+    assert!(Layout::<2>::valid(constants::VALID_STATUS_MASK));
+    assert!(!Layout::<2>::valid(constants::VALID_STATUS_MASK + 1));
+    for i in 0..=1 {
+        assert_eq!(Layout::<2>::active_slice(i), i as u8);
+    }
+    assert_eq!(StatusLayout::<2>::inc(1), constants::SLICE_2_INC);
+    assert_eq!(StatusLayout::<2>::INDEX_MASK, constants::CURRENT_SLICE_MASK);
+}
+
+#[test]
+fn test_extra_buffers_avoid_waiting() {
+    let atomic_slice = AtomicSlice::<u8, 3>::new_with_buffers(vec![0; 4]);
+    let first_guard = atomic_slice.read();
+    for i in 1..=10 {
+        assert_eq!(atomic_slice.try_write(&[i; 4]), Ok(()));
+    }
+    // Holding onto a second version leaves no free buffers
+    let last_guard = atomic_slice.read();
+    assert_eq!(atomic_slice.try_write(&[11; 4]), Ok(()));
+    assert_eq!(
+        atomic_slice.try_write(&[12; 4]),
+        Err(TryWriteError::ReadInProgress)
+    );
+    assert_eq!(*first_guard, [0; 4]);
+    assert_eq!(*last_guard, [10; 4]);
+    assert_eq!(*atomic_slice.read(), [11; 4]);
+    assert_eq!(atomic_slice.version(), 11);
+}
+
+static ATOMIC_ARRAY: AtomicArray<u32, 4> = AtomicArray::new([0; 4]);
+
+#[test]
+fn test_atomic_array() {
+    assert_eq!(ATOMIC_ARRAY.len(), 4);
+    assert_eq!(*ATOMIC_ARRAY.read(), [0; 4]);
+
+    let readers: Vec<std::thread::JoinHandle<()>> = (0..4)
+        .map(|_| {
+            std::thread::spawn(|| {
+                for _ in 0..1000 {
+                    let guard = ATOMIC_ARRAY.read();
+                    assert_eq!(*guard, [guard.version() as u32; 4]);
+                }
+            })
+        })
+        .collect();
+    for i in 1..=100 {
+        ATOMIC_ARRAY.write(&[i; 4]);
+    }
+    for r in readers {
+        r.join().unwrap();
+    }
+    assert_eq!(ATOMIC_ARRAY.version(), 100);
+
+    let guard = ATOMIC_ARRAY.read();
+    ATOMIC_ARRAY.write_with(|array| *array = [101; 4]);
+    assert_eq!(
+        ATOMIC_ARRAY.try_write(&[102; 4]),
+        Err(TryWriteError::ReadInProgress)
+    );
+    assert_eq!(*guard, [100; 4]);
+    drop(guard);
+    assert_eq!(ATOMIC_ARRAY.try_write(&[102; 4]), Ok(()));
+    assert_eq!(*ATOMIC_ARRAY.read(), [102; 4]);
+}
+
+#[test]
+fn test_atomic_slice_inline() {
+    let atomic_slice = AtomicSliceInline::new_arc(vec![0_u32; 5]);
+    assert_eq!(atomic_slice.len(), 5);
+    assert_eq!(*atomic_slice.read(), [0; 5]);
+
+    let readers: Vec<std::thread::JoinHandle<()>> = (0..4)
+        .map(|_| {
+            let atomic_slice = Arc::clone(&atomic_slice);
+            std::thread::spawn(move || {
+                for _ in 0..1000 {
+                    let guard = atomic_slice.read();
+                    assert_eq!(*guard, [guard.version() as u32; 5]);
+                }
+            })
+        })
+        .collect();
+    for i in 1..=100 {
+        atomic_slice.write(&[i; 5]);
+    }
+    for r in readers {
+        r.join().unwrap();
+    }
+
+    let guard = atomic_slice.read();
+    atomic_slice.write_with(|slice| slice.fill(101));
+    assert_eq!(
+        atomic_slice.try_write(&[102; 5]),
+        Err(TryWriteError::ReadInProgress)
+    );
+    assert_eq!(*guard, [100; 5]);
+    drop(guard);
+    assert_eq!(atomic_slice.try_write(&[102; 5]), Ok(()));
+    assert_eq!(*atomic_slice.read(), [102; 5]);
+
+    // Elements are dropped exactly once along with the box
+    let counter = Arc::new(());
+    let boxed = AtomicSliceInline::new_boxed(vec![Arc::clone(&counter); 3]);
+    assert_eq!(Arc::strong_count(&counter), 7);
+    drop(boxed);
+    assert_eq!(Arc::strong_count(&counter), 1);
+
+    assert!(AtomicSliceInline::<String>::new_boxed(Vec::new()).is_empty());
+}
+
+#[test]
+fn test_status_cache_line() {
+    // The status word starts a cache line of its own, wherever it is stored
+    let atomic_slice = AtomicSlice::new(vec![0_u8; 3]);
+    assert_eq!(unsafe { atomic_slice.raw_status() } as usize % 64, 0);
+    assert!(std::mem::align_of_val(&*AtomicSliceInline::new_boxed(vec![0_u8; 3])) >= 64);
+    assert!(std::mem::align_of::<AtomicArray<u8, 3>>() >= 64);
+}
+
+#[test]
+fn test_too_many_reads() {
+    let atomic_slice = AtomicSlice::<u8, 8>::new_with_buffers(vec![1; 3]);
+    let mut guards: Vec<_> = (0..Layout::<8>::MAX_COUNT)
+        .map(|_| atomic_slice.read())
+        .collect();
+
+    // Going over the limit panics instead of overflowing into other counts
+    let read = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let _guard = atomic_slice.read();
+    }));
+    assert!(read.is_err());
+    let clone = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let _guard = guards[0].clone();
+    }));
+    assert!(clone.is_err());
+    assert!(atomic_slice.try_read().is_none());
+
+    // Reading succeeds again once a guard is dropped
+    guards.pop();
+    assert_eq!(*atomic_slice.try_read().unwrap(), [1; 3]);
+    assert_eq!(*atomic_slice.read(), [1; 3]);
+    atomic_slice.write(&[2; 3]);
+    assert_eq!(*atomic_slice.read(), [2; 3]);
+    assert_eq!(*guards[0], [1; 3]);
+
+    guards.clear();
+    let status = unsafe { (*atomic_slice.raw_status()).load(Ordering::SeqCst) };
+    for i in 0..8 {
+        assert_eq!(Layout::<8>::use_count(i, status), 0);
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_poisoning() {
+    #[derive(Debug, PartialEq)]
+    struct Fragile(u32);
+
+    impl Clone for Fragile {
+        fn clone(&self) -> Fragile {
+            if self.0 == 13 {
+                panic!("Unlucky number");
+            }
+            Fragile(self.0)
+        }
+    }
+
+    let atomic_slice = AtomicSlice::new(vec![Fragile(1), Fragile(2)]);
+    assert!(!atomic_slice.is_poisoned());
+
+    let write = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        atomic_slice.write(&[Fragile(3), Fragile(13)]);
+    }));
+    assert!(write.is_err());
+    assert!(atomic_slice.is_poisoned());
+    assert_eq!(*atomic_slice.read(), [Fragile(1), Fragile(2)]);
+
+    // The writer lock was released, so writing still works
+    atomic_slice.write(&[Fragile(4), Fragile(5)]);
+    assert_eq!(*atomic_slice.read(), [Fragile(4), Fragile(5)]);
+    assert!(atomic_slice.is_poisoned());
+    atomic_slice.clear_poison();
+    assert!(!atomic_slice.is_poisoned());
+
+    // The partition that was left half written is never read from again
+    atomic_slice.write(&[Fragile(6), Fragile(7)]);
+    let write = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        atomic_slice.write(&[Fragile(8), Fragile(13)]);
+    }));
+    assert!(write.is_err());
+    let pair = atomic_slice.read_with_previous();
+    assert_eq!(**pair.previous(), [Fragile(6), Fragile(7)]);
+    assert_eq!(pair.previous().version(), 2);
+    drop(pair);
+    atomic_slice.clear_poison();
+
+    // Neither is it read back, even if it held a version still wanted
+    let atomic_slice = AtomicSlice::<_, 3>::new_with_buffers(vec![Fragile(1)]);
+    for i in 2..=4 {
+        atomic_slice.write(&[Fragile(i)]);
+    }
+    let write = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        atomic_slice.write_with(|slice| {
+            slice[0] = Fragile(5);
+            panic!("Interrupted write");
+        });
+    }));
+    assert!(write.is_err());
+    assert_eq!(*atomic_slice.read_back(1).unwrap(), [Fragile(3)]);
+    assert!(atomic_slice.read_back(2).is_none());
+
+    let sharded = ShardedAtomicSlice::<u8, 2>::new(vec![0; 2]);
+    let write = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        sharded.write_with(|_| panic!("Gave up"));
+    }));
+    assert!(write.is_err());
+    assert!(sharded.is_poisoned());
+    assert_eq!(sharded.try_write(&[1, 2]), Ok(()));
+}
+
+#[cfg(all(debug_assertions, feature = "std"))]
+#[test]
+fn test_write_while_reading() {
+    let atomic_slice = AtomicSlice::new(vec![0_u8; 4]);
+
+    // The guard pins the only partition that the second write could use
+    let write = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let _guard = atomic_slice.read();
+        atomic_slice.write(&[1; 4]);
+        atomic_slice.write(&[2; 4]);
+    }));
+    assert!(write.is_err());
+    assert_eq!(*atomic_slice.read(), [1; 4]);
+
+    // Waiting with a timeout for a guard that was handed to another thread
+    // doesn't panic
+    std::thread::scope(|s| {
+        let guard = atomic_slice.read();
+        atomic_slice.write(&[2; 4]);
+        s.spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            drop(guard);
+        });
+        assert_eq!(
+            atomic_slice.write_timeout(&[3; 4], Duration::from_secs(10)),
+            Ok(())
+        );
+    });
+    assert_eq!(*atomic_slice.read(), [3; 4]);
+
+    // Guards released by another thread are no longer held by this one, so
+    // waiting for a different reader of the same partition is fine
+    let atomic_slice = AtomicSlice::new(vec![0_u8; 4]);
+    std::thread::scope(|s| {
+        let guard = atomic_slice.read();
+        s.spawn(move || drop(guard)).join().unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let atomic_slice = &atomic_slice;
+        s.spawn(move || {
+            let _guard = atomic_slice.read();
+            tx.send(()).unwrap();
+            std::thread::sleep(Duration::from_millis(10));
+        });
+        rx.recv().unwrap();
+        atomic_slice.write(&[1; 4]);
+        atomic_slice.write(&[2; 4]);
+    });
+    assert_eq!(*atomic_slice.read(), [2; 4]);
+
+    // With a spare partition, writing can carry on regardless
+    let atomic_slice = AtomicSlice::<u8, 3>::new_with_buffers(vec![0; 4]);
+    let guard = atomic_slice.read();
+    atomic_slice.write(&[1; 4]);
+    atomic_slice.write(&[2; 4]);
+    assert_eq!(*guard, [0; 4]);
+}
+
+#[cfg(all(debug_assertions, feature = "std"))]
+#[test]
+fn test_read_locks_released_elsewhere() {
+    // Guards that are always released by another thread don't pile up in the
+    // registry of the thread that acquired them
+    let atomic_slice = AtomicSlice::new(vec![0_u8; 4]);
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::scope(|s| {
+        s.spawn(move || rx.into_iter().for_each(drop));
+        for _ in 0..1000 {
+            tx.send(atomic_slice.read()).unwrap();
+        }
+        drop(tx);
+    });
+    let guard = atomic_slice.read();
+    assert_eq!(crate::registry::held_count(), 1);
+    drop(guard);
+    assert_eq!(crate::registry::held_count(), 0);
+
+    // A lock released by another thread could belong to any of the threads
+    // reading the same partition, none of which are then known to hold it
+    let atomic_slice = AtomicSlice::new(vec![0_u8; 4]);
+    std::thread::scope(|s| {
+        let (tx_locked, rx_locked) = std::sync::mpsc::channel();
+        let (tx_release, rx_release) = std::sync::mpsc::channel::<()>();
+        let atomic_slice = &atomic_slice;
+        s.spawn(move || {
+            let _guard = atomic_slice.read();
+            tx_locked.send(()).unwrap();
+            rx_release.recv().unwrap();
+        });
+        rx_locked.recv().unwrap();
+        let guard = atomic_slice.read();
+        s.spawn(move || drop(guard)).join().unwrap();
+
+        atomic_slice.write(&[1; 4]);
+        s.spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            tx_release.send(()).unwrap();
+        });
+        atomic_slice.write(&[2; 4]);
+    });
+    assert_eq!(*atomic_slice.read(), [2; 4]);
+    assert_eq!(crate::registry::held_count(), 0);
+}
+
+#[test]
+fn test_sharded_atomic_slice() {
+    let atomic_slice = Arc::new(ShardedAtomicSlice::<u32, 4>::new(vec![0; 5]));
+    assert_eq!(atomic_slice.len(), 5);
+    // Miri is far too slow for the full number of iterations
+    let (reads, writes) = if cfg!(miri) {
+        (100, 10)
+    } else {
+        (10_000, 1000)
+    };
+
+    // More threads than shards, so that some of them share a shard
+    let readers: Vec<std::thread::JoinHandle<()>> = (0..8)
+        .map(|_| {
+            let atomic_slice = Arc::clone(&atomic_slice);
+            std::thread::spawn(move || {
+                for _ in 0..reads {
+                    let guard = atomic_slice.read();
+                    assert_eq!(*guard, [guard.version() as u32; 5]);
+                }
+            })
+        })
+        .collect();
+    for i in 1..=writes {
+        atomic_slice.write(&[i; 5]);
+    }
+    for r in readers {
+        r.join().unwrap();
+    }
+    assert_eq!(atomic_slice.version(), writes as u64);
+
+    // A guard held by another thread, and thus likely in another shard,
+    // still keeps its slice from being written to
+    let (tx_locked, rx_locked) = std::sync::mpsc::channel();
+    let (tx_release, rx_release) = std::sync::mpsc::channel::<()>();
+    let holder = {
+        let atomic_slice = Arc::clone(&atomic_slice);
+        std::thread::spawn(move || {
+            let guard = atomic_slice.read();
+            tx_locked.send(()).unwrap();
+            rx_release.recv().unwrap();
+            assert_eq!(*guard, [writes; 5]);
+        })
+    };
+    rx_locked.recv().unwrap();
+    atomic_slice.write(&[1001; 5]);
+    assert_eq!(
+        atomic_slice.try_write(&[1002; 5]),
+        Err(TryWriteError::ReadInProgress)
+    );
+    tx_release.send(()).unwrap();
+    holder.join().unwrap();
+    assert_eq!(atomic_slice.try_write(&[1002; 5]), Ok(()));
+    assert_eq!(*atomic_slice.read(), [1002; 5]);
+}
+
+#[cfg(feature = "status-u128")]
+#[test]
+fn test_wide_status() {
+    assert_eq!(Layout::<2>::MAX_COUNT, u32::MAX as u128);
+    assert_eq!(Layout::<3>::MAX_COUNT, u32::MAX as u128);
+    assert_eq!(Layout::<8>::MAX_COUNT, 16383);
+
+    // More guards than fit into a 16-bit use count
+    let atomic_slice = AtomicSlice::new(vec![1_u8; 3]);
+    let guards: Vec<_> = (0..100_000).map(|_| atomic_slice.read()).collect();
+    assert_eq!(atomic_slice.try_write(&[2; 3]), Ok(()));
+    assert_eq!(
+        atomic_slice.try_write(&[3; 3]),
+        Err(TryWriteError::ReadInProgress)
+    );
+    assert!(guards.iter().all(|g| **g == [1; 3]));
+    drop(guards);
+    atomic_slice.write(&[3; 3]);
+    assert_eq!(*atomic_slice.read(), [3; 3]);
+}
+
+/// Everything that works without a second thread, which is all there is on
+/// WebAssembly without the `atomics` target feature
+#[test]
+fn test_single_threaded() {
+    let atomic_slice = AtomicSlice::new(vec![0_u16; 4]);
+    atomic_slice.write(&[1; 4]);
+    atomic_slice.write_with(|slice| slice.fill(2));
+    atomic_slice.update(|previous, slice| {
+        for (v, p) in slice.iter_mut().zip(previous) {
+            *v = p + 1;
+        }
+    });
+    assert_eq!(*atomic_slice.read(), [3; 4]);
+    #[cfg(feature = "std")]
+    assert_eq!(atomic_slice.wait_for_update(2), 3);
+
+    // A guard only pins its own partition, and the other one is only needed
+    // by the write after next
+    let guard = atomic_slice.read();
+    atomic_slice.write(&[4; 4]);
+    assert_eq!(
+        atomic_slice.try_write(&[5; 4]),
+        Err(TryWriteError::ReadInProgress)
+    );
+    assert_eq!(*guard, [3; 4]);
+    drop(guard);
+    assert_eq!(atomic_slice.try_write(&[5; 4]), Ok(()));
+
+    // With a spare partition, holding onto a guard never gets in the way
+    let atomic_slice = Arc::new(AtomicSlice::<u16, 3>::new_with_buffers(vec![0; 4]));
+    let guard = atomic_slice.read_arc();
+    for i in 1..10 {
+        atomic_slice.write(&[i; 4]);
+        assert_eq!(*atomic_slice.snapshot_arc(), [i; 4]);
+    }
+    assert_eq!(*guard, [0; 4]);
+}
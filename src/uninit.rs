@@ -0,0 +1,71 @@
+use alloc::boxed::Box;
+use core::mem::MaybeUninit;
+
+use crate::{storage::Storage, AtomicSlice};
+
+/// The memory for an `AtomicSlice` which has been allocated but not filled
+/// in yet. Created using `AtomicSlice::new_uninit` and turned into an
+/// `AtomicSlice` by its first `write`.
+///
+/// ```
+/// use atomicslice::AtomicSlice;
+///
+/// let table: Vec<f32> = (0..1024).map(|i| i as f32).collect();
+/// let atomic_slice: AtomicSlice<f32> = AtomicSlice::new_uninit(1024).write(&table);
+/// assert_eq!(atomic_slice.read()[1023], 1023.0);
+/// ```
+pub struct UninitAtomicSlice<T, const BUFS: usize = 2> {
+    data: Box<[MaybeUninit<T>]>,
+    stride: usize,
+}
+
+impl<T, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Allocate the memory for an `AtomicSlice` of the given length without
+    /// initializing it, which saves filling in very large slices twice when
+    /// their data is only known later. Nothing can be read until the first
+    /// `write` turns the result into an `AtomicSlice`.
+    pub fn new_uninit(len: usize) -> UninitAtomicSlice<T, BUFS> {
+        let total = len
+            .checked_mul(BUFS)
+            .expect("Attempted to create AtomicSlice whose partitions don't fit into memory");
+        UninitAtomicSlice {
+            data: Box::new_uninit_slice(total),
+            stride: len,
+        }
+    }
+}
+
+impl<T, const BUFS: usize> UninitAtomicSlice<T, BUFS> {
+    /// Get the number of elements that the `AtomicSlice` will have
+    pub fn len(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns true if the `AtomicSlice` will have no elements
+    pub fn is_empty(&self) -> bool {
+        self.stride == 0
+    }
+}
+
+impl<T: Clone, const BUFS: usize> UninitAtomicSlice<T, BUFS> {
+    /// Write the initial data, which must have the length passed to
+    /// `new_uninit`, otherwise this method panics. The data is cloned
+    /// straight into each partition, and the resulting `AtomicSlice` is
+    /// returned.
+    pub fn write(mut self, data: &[T]) -> AtomicSlice<T, BUFS> {
+        if data.len() != self.stride {
+            panic!("Attempted to write slice of the wrong length to AtomicSlice");
+        }
+
+        // If cloning panics, the elements cloned so far are leaked
+        for partition in self.data.chunks_exact_mut(self.stride.max(1)) {
+            for (element, value) in partition.iter_mut().zip(data) {
+                element.write(value.clone());
+            }
+        }
+
+        // Safety: every element of every partition was just written
+        let data = unsafe { self.data.assume_init() };
+        AtomicSlice::from_storage(Storage::new(data))
+    }
+}
@@ -0,0 +1,177 @@
+use core::{
+    cell::UnsafeCell,
+    ops::Deref,
+    panic::{RefUnwindSafe, UnwindSafe},
+};
+
+use crate::{
+    protocol::{release, Protocol},
+    sync::AtomicStatus,
+    Backoff, TryWriteError,
+};
+
+/// A single value that can be written and read from multiple threads,
+/// just like the elements of an `AtomicSlice` and with the same cost of
+/// reading. Both copies of the value are stored inline.
+///
+/// ```
+/// use atomicslice::AtomicValue;
+///
+/// let envelope = AtomicValue::new((0.01, 0.5));
+/// envelope.write((0.02, 0.25));
+/// assert_eq!(*envelope.read(), (0.02, 0.25));
+/// ```
+pub struct AtomicValue<T> {
+    data: UnsafeCell<[T; 2]>,
+    protocol: Protocol<2>,
+}
+
+/// A smart pointer type representing read-only access to the value in an
+/// `AtomicValue`, which works just like an `AtomicSliceReadGuard`.
+pub struct AtomicValueReadGuard<'a, T> {
+    value: &'a T,
+    current_slice: u8,
+    status: &'a AtomicStatus,
+    version: u64,
+}
+
+impl<T: Clone> AtomicValue<T> {
+    /// Create a new `AtomicValue` holding the given value
+    pub fn new(value: T) -> AtomicValue<T> {
+        AtomicValue {
+            data: UnsafeCell::new([value.clone(), value]),
+            protocol: Protocol::new(),
+        }
+    }
+}
+
+impl<T> AtomicValue<T> {
+    /// Get the version of the most recently published value. See
+    /// `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.protocol.version()
+    }
+
+    /// Acquire a read lock on the value. See `AtomicSlice::read`.
+    pub fn read(&self) -> AtomicValueReadGuard<'_, T> {
+        let current_slice = self.protocol.acquire();
+        let value = unsafe { self.value(current_slice) };
+        let version = unsafe { self.protocol.slice_version(current_slice) };
+
+        AtomicValueReadGuard {
+            value,
+            current_slice,
+            status: self.protocol.status(),
+            version,
+        }
+    }
+
+    /// Block the current thread until a version newer than `since_version`
+    /// has been published. See `AtomicSlice::wait_for_update`.
+    #[cfg(feature = "std")]
+    pub fn wait_for_update(&self, since_version: u64) -> u64 {
+        self.protocol.wait_for_update(since_version)
+    }
+
+    /// Choose how writers wait. See `AtomicSlice::set_backoff`.
+    pub fn set_backoff(&mut self, backoff: Backoff) {
+        self.protocol.set_backoff(backoff);
+    }
+
+    /// Returns true if a thread panicked while writing. See
+    /// `AtomicSlice::is_poisoned`.
+    #[cfg(feature = "std")]
+    pub fn is_poisoned(&self) -> bool {
+        self.protocol.is_poisoned()
+    }
+
+    /// Clear the poisoned state after a panic while writing. See
+    /// `AtomicSlice::clear_poison`.
+    #[cfg(feature = "std")]
+    pub fn clear_poison(&self) {
+        self.protocol.clear_poison();
+    }
+
+    /// Write a new value in place. See `AtomicSlice::write_with`.
+    pub fn write_with<F: FnOnce(&mut T)>(&self, f: F) {
+        let _lock = self.protocol.lock_writer();
+
+        // Wait to ensure the next value is not being used
+        let next_i = self.protocol.wait_for_free_slice();
+
+        // Let the caller fill in the next value
+        f(unsafe { self.value_mut(next_i) });
+
+        self.protocol.publish(next_i);
+    }
+
+    /// Write a new value, moving it into the `AtomicValue`. This method may
+    /// block if other threads are writing and if any readers are holding lock
+    /// guards for extended periods of time.
+    pub fn write(&self, value: T) {
+        self.write_with(|v| *v = value);
+    }
+
+    /// Attempt to write a new value without waiting. See
+    /// `AtomicSlice::try_write`. The value is given back if nothing was
+    /// written.
+    pub fn try_write(&self, value: T) -> Result<(), (T, TryWriteError)> {
+        let Some(_lock) = self.protocol.try_lock_writer() else {
+            return Err((value, TryWriteError::WriteInProgress));
+        };
+
+        let Some(next_i) = self.protocol.free_slice() else {
+            return Err((value, TryWriteError::ReadInProgress));
+        };
+
+        *unsafe { self.value_mut(next_i) } = value;
+
+        self.protocol.publish(next_i);
+        Ok(())
+    }
+
+    /// Get shared access to one of the two values.
+    ///
+    /// Safety: the value must not be written to while the returned reference
+    /// is alive, i.e. the caller must either hold a read lock on it or hold
+    /// exclusive access to the write portion.
+    unsafe fn value(&self, slice: u8) -> &T {
+        &*(self.data.get() as *const T).add(slice as usize)
+    }
+
+    /// Get mutable access to one of the two values.
+    ///
+    /// Safety: the caller must hold exclusive access to the write portion
+    /// and the value must not be in use by any readers.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn value_mut(&self, slice: u8) -> &mut T {
+        &mut *(self.data.get() as *mut T).add(slice as usize)
+    }
+}
+
+unsafe impl<T: Send + Sync> Sync for AtomicValue<T> {}
+unsafe impl<T: Send> Send for AtomicValue<T> {}
+impl<T: RefUnwindSafe> RefUnwindSafe for AtomicValue<T> {}
+impl<T: UnwindSafe> UnwindSafe for AtomicValue<T> {}
+
+impl<'a, T> Deref for AtomicValueReadGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<'a, T> Drop for AtomicValueReadGuard<'a, T> {
+    fn drop(&mut self) {
+        release::<2>(self.status, self.current_slice);
+    }
+}
+
+impl<'a, T> AtomicValueReadGuard<'a, T> {
+    /// The version of the value that this guard is holding onto.
+    /// See `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+}
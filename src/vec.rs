@@ -0,0 +1,158 @@
+use alloc::vec::Vec;
+use core::{
+    cell::UnsafeCell,
+    panic::{RefUnwindSafe, UnwindSafe},
+};
+
+use crate::{protocol::Protocol, AtomicSliceReadGuard, Backoff};
+
+/// A variant of `AtomicSlice` whose length may change with every write.
+/// Each of the `BUFS` partitions is a vector of its own, which writers may
+/// resize or replace at will, while readers always see the coherent contents
+/// of a single write, together with their length.
+///
+/// ```
+/// use atomicslice::AtomicVec;
+///
+/// let presets = AtomicVec::new(vec![1, 2, 3]);
+/// presets.write(&[4, 5]);
+/// assert_eq!(*presets.read(), [4, 5]);
+/// ```
+///
+/// Reading returns the same lock guard as `AtomicSlice::read`.
+pub struct AtomicVec<T, const BUFS: usize = 2> {
+    data: UnsafeCell<[Vec<T>; BUFS]>,
+    protocol: Protocol<BUFS>,
+}
+
+impl<T: Clone> AtomicVec<T> {
+    /// Create a new `AtomicVec` from a vector of data, which it starts out
+    /// with
+    pub fn new(data: Vec<T>) -> AtomicVec<T> {
+        AtomicVec::new_with_buffers(data)
+    }
+}
+
+impl<T: Clone, const BUFS: usize> AtomicVec<T, BUFS> {
+    /// Create a new `AtomicVec` with `BUFS` partitions from a vector of data.
+    /// Otherwise identical to `new`.
+    pub fn new_with_buffers(data: Vec<T>) -> AtomicVec<T, BUFS> {
+        let protocol = Protocol::new();
+        AtomicVec {
+            data: UnsafeCell::new(core::array::from_fn(|_| data.clone())),
+            protocol,
+        }
+    }
+
+    /// Write a slice of new data, which may have any length. The vector of
+    /// the partition being written to keeps its allocation if it is large
+    /// enough.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn write(&self, data: &[T]) {
+        self.write_with(|vec| {
+            vec.clear();
+            vec.extend_from_slice(data);
+        });
+    }
+}
+
+impl<T, const BUFS: usize> AtomicVec<T, BUFS> {
+    /// Get the version of the most recently published data. See
+    /// `AtomicSlice::version`.
+    pub fn version(&self) -> u64 {
+        self.protocol.version()
+    }
+
+    /// Acquire a read lock on the most recently published data, whose length
+    /// is that of the slice that the guard dereferences to. See
+    /// `AtomicSlice::read`.
+    pub fn read(&self) -> AtomicSliceReadGuard<'_, T, BUFS> {
+        let current_slice = self.protocol.acquire();
+        let slice: &[T] = unsafe { self.vec(current_slice) };
+        let version = unsafe { self.protocol.slice_version(current_slice) };
+
+        AtomicSliceReadGuard {
+            slice,
+            current_slice,
+            status: self.protocol.status(),
+            version,
+        }
+    }
+
+    /// Block the current thread until a version newer than `since_version`
+    /// has been published. See `AtomicSlice::wait_for_update`.
+    #[cfg(feature = "std")]
+    pub fn wait_for_update(&self, since_version: u64) -> u64 {
+        self.protocol.wait_for_update(since_version)
+    }
+
+    /// Choose how writers wait. See `AtomicSlice::set_backoff`.
+    pub fn set_backoff(&mut self, backoff: Backoff) {
+        self.protocol.set_backoff(backoff);
+    }
+
+    /// Returns true if a thread panicked while writing. See
+    /// `AtomicSlice::is_poisoned`.
+    #[cfg(feature = "std")]
+    pub fn is_poisoned(&self) -> bool {
+        self.protocol.is_poisoned()
+    }
+
+    /// Clear the poisoned state after a panic while writing. See
+    /// `AtomicSlice::clear_poison`.
+    #[cfg(feature = "std")]
+    pub fn clear_poison(&self) {
+        self.protocol.clear_poison();
+    }
+
+    /// Write new data in place. The given function receives mutable access
+    /// to the vector of the inactive partition, which it may resize or even
+    /// replace entirely, and whatever it leaves there is published to readers
+    /// once it returns. Like with `AtomicSlice::write_with`, the vector still
+    /// holds stale data from an earlier write.
+    pub fn write_with<F: FnOnce(&mut Vec<T>)>(&self, f: F) {
+        let _lock = self.protocol.lock_writer();
+
+        // Wait to ensure the next vector is not being used
+        let next_i = self.protocol.wait_for_free_slice();
+
+        // Let the caller fill in the next vector
+        f(unsafe { self.vec_mut(next_i) });
+
+        self.protocol.publish(next_i);
+    }
+
+    /// Write a vector of new data, which replaces the vector of the partition
+    /// being written to without copying any elements.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn write_vec(&self, data: Vec<T>) {
+        self.write_with(|vec| *vec = data);
+    }
+
+    /// Get shared access to one of the vectors.
+    ///
+    /// Safety: the vector must not be written to while the returned reference
+    /// is alive, i.e. the caller must either hold a read lock on it or hold
+    /// exclusive access to the write portion.
+    unsafe fn vec(&self, slice: u8) -> &Vec<T> {
+        &*(self.data.get() as *const Vec<T>).add(slice as usize)
+    }
+
+    /// Get mutable access to one of the vectors.
+    ///
+    /// Safety: the caller must hold exclusive access to the write portion
+    /// and the vector must not be in use by any readers.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn vec_mut(&self, slice: u8) -> &mut Vec<T> {
+        &mut *(self.data.get() as *mut Vec<T>).add(slice as usize)
+    }
+}
+
+unsafe impl<T: Send + Sync, const BUFS: usize> Sync for AtomicVec<T, BUFS> {}
+unsafe impl<T: Send, const BUFS: usize> Send for AtomicVec<T, BUFS> {}
+impl<T: RefUnwindSafe, const BUFS: usize> RefUnwindSafe for AtomicVec<T, BUFS> {}
+impl<T: UnwindSafe, const BUFS: usize> UnwindSafe for AtomicVec<T, BUFS> {}
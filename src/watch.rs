@@ -0,0 +1,89 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{AtomicSlice, MappedAtomicSliceReadGuard};
+
+/// A handle which finds out whether a particular range of elements of an
+/// `AtomicSlice` has changed, so that consumers interested in only a few
+/// elements can ignore writes that leave those elements alone. Created
+/// using `AtomicSlice::subscribe_range`.
+///
+/// The range counts as changed once its contents differ from the ones most
+/// recently seen through `read` or `mark_seen`, or from the ones at the time
+/// of subscribing. The range is compared at most once per published version,
+/// and only when asked through `has_changed` or `wait`. Changes that are
+/// undone again before being noticed go unnoticed.
+pub struct RangeSubscription<'a, T, const BUFS: usize = 2> {
+    atomic_slice: &'a AtomicSlice<T, BUFS>,
+    range: Range<usize>,
+    /// A copy of the contents of the range that were last seen
+    seen: Vec<T>,
+    /// The version that the range was last compared at
+    checked_version: u64,
+    /// Whether the range differed from `seen` at `checked_version`
+    changed: bool,
+}
+
+impl<T: Clone + PartialEq, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Subscribe to changes of the elements within the given range, which
+    /// must lie within the `AtomicSlice`, otherwise this method panics. See
+    /// `RangeSubscription` for details.
+    pub fn subscribe_range(&self, range: Range<usize>) -> RangeSubscription<'_, T, BUFS> {
+        let guard = self.read();
+        RangeSubscription {
+            atomic_slice: self,
+            seen: guard[range.clone()].to_vec(),
+            range,
+            checked_version: guard.version(),
+            changed: false,
+        }
+    }
+}
+
+impl<'a, T: Clone + PartialEq, const BUFS: usize> RangeSubscription<'a, T, BUFS> {
+    /// The range of elements being watched
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns true if the contents of the range differ from the ones last
+    /// seen. This does not mark them as seen.
+    pub fn has_changed(&mut self) -> bool {
+        // Nothing can have changed without a newer version being published
+        if self.atomic_slice.version() == self.checked_version {
+            return self.changed;
+        }
+
+        let guard = self.atomic_slice.read();
+        self.changed = guard[self.range.clone()] != *self.seen;
+        self.checked_version = guard.version();
+        self.changed
+    }
+
+    /// Acquire a read lock on just the range and mark its current contents
+    /// as seen.
+    pub fn read(&mut self) -> MappedAtomicSliceReadGuard<'a, T, BUFS> {
+        let guard = self.atomic_slice.read();
+        self.seen.clone_from_slice(&guard[self.range.clone()]);
+        self.checked_version = guard.version();
+        self.changed = false;
+
+        let range = self.range.clone();
+        guard.map(|slice| &slice[range])
+    }
+
+    /// Mark the current contents of the range as seen, without looking at
+    /// them.
+    pub fn mark_seen(&mut self) {
+        self.read();
+    }
+
+    /// Block the current thread until the contents of the range differ from
+    /// the ones last seen. Returns immediately if they already do.
+    #[cfg(feature = "std")]
+    pub fn wait(&mut self) {
+        while !self.has_changed() {
+            self.atomic_slice.wait_for_update(self.checked_version);
+        }
+    }
+}
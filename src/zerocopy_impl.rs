@@ -0,0 +1,57 @@
+use alloc::vec::Vec;
+
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
+
+use crate::{AtomicSlice, AtomicSliceReadGuard, LengthMismatch};
+
+impl<T: FromBytes + Clone, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Create a new `AtomicSlice` from raw bytes, which don't need to be
+    /// aligned for `T`. Returns `None` if the number of bytes isn't a
+    /// multiple of the size of `T`, or if `T` is zero-sized.
+    pub fn from_bytes(bytes: &[u8]) -> Option<AtomicSlice<T, BUFS>> {
+        let size = core::mem::size_of::<T>();
+        if size == 0 || !bytes.len().is_multiple_of(size) {
+            return None;
+        }
+
+        let data: Vec<T> = bytes
+            .chunks_exact(size)
+            .map(|chunk| T::read_from_bytes(chunk).unwrap())
+            .collect();
+        Some(AtomicSlice::new_with_buffers(data))
+    }
+}
+
+impl<T: FromBytes + IntoBytes, const BUFS: usize> AtomicSlice<T, BUFS> {
+    /// Write new data given as raw bytes, which are copied into place as-is.
+    /// Like `write_bytes` with the `bytemuck` feature, the bytes don't need
+    /// to be aligned for `T`, but there must be exactly as many of them as the
+    /// `AtomicSlice` holds, otherwise nothing is written and an error is
+    /// returned whose lengths are counted in bytes.
+    ///
+    /// This method may block if other threads are writing and if any readers
+    /// are holding lock guards for extended periods of time.
+    pub fn write_from_bytes(&self, bytes: &[u8]) -> Result<(), LengthMismatch> {
+        let expected = self.stride * core::mem::size_of::<T>();
+        if bytes.len() != expected {
+            return Err(LengthMismatch {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+
+        self.write_with(|slice| slice.as_mut_bytes().copy_from_slice(bytes));
+        Ok(())
+    }
+}
+
+impl<'a, T: IntoBytes + Immutable, const BUFS: usize> AtomicSliceReadGuard<'a, T, BUFS> {
+    /// View the locked data as a slice of a different type with a compatible
+    /// layout. Returns `None` if the data isn't suitably aligned for `U`, or
+    /// if its size in bytes isn't a multiple of the size of `U`.
+    ///
+    /// The raw bytes themselves are available through `IntoBytes::as_bytes`.
+    pub fn read_as<U: FromBytes + Immutable + KnownLayout>(&self) -> Option<&[U]> {
+        <[U]>::ref_from_bytes(self.slice.as_bytes()).ok()
+    }
+}